@@ -0,0 +1,28 @@
+using RsMem.Interop;
+using Xunit;
+
+namespace RsMem.Tests;
+
+public class AbiTests
+{
+    [Fact]
+    public void VersionMatchesLibrary()
+    {
+        Assert.Equal(NativeMethods.rsmem_abi_version(), Abi.Version);
+    }
+
+    [Fact]
+    public void StructSizesMatchLibrary()
+    {
+        var sizes = Abi.StructSizes();
+
+        foreach (var id in Enum.GetValues<AbiStruct>())
+            Assert.True(NativeMethods.rsmem_struct_size(id) == sizes[(int)id], $"{id} is {sizes[(int)id]} bytes here, {NativeMethods.rsmem_struct_size(id)} in the library");
+    }
+
+    [Fact]
+    public void HandshakeSucceeds()
+    {
+        Abi.Handshake();
+    }
+}
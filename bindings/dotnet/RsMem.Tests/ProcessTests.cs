@@ -0,0 +1,129 @@
+using System.Diagnostics;
+using System.Text;
+using RsMem.Interop;
+using Xunit;
+
+namespace RsMem.Tests;
+
+public sealed class LinuxFactAttribute : FactAttribute
+{
+    public LinuxFactAttribute()
+    {
+        if (!OperatingSystem.IsLinux())
+            Skip = "attaches a child process, linux only";
+    }
+}
+
+/// <summary>
+/// Runs against a `sleep` child whose environment carries a marker string, so there is known
+/// data on its stack. The tests of a class run one after another, only one child is attached at
+/// a time.
+/// </summary>
+public sealed class ProcessTests : IDisposable
+{
+    private readonly Process _child;
+    private readonly string _marker = $"rsmem-marker-{Guid.NewGuid():N}";
+
+    public ProcessTests()
+    {
+        var info = new ProcessStartInfo("/bin/sleep", "60") { UseShellExecute = false };
+        info.Environment["RSMEM_TEST_MARKER"] = _marker;
+
+        _child = Process.Start(info)!;
+    }
+
+    public void Dispose()
+    {
+        _child.Kill();
+        _child.WaitForExit();
+        _child.Dispose();
+    }
+
+    private uint Pid => (uint)_child.Id;
+
+    private nuint FindMarker(RsProcess process)
+    {
+        var matches = process.FindString(_marker, TextEncoding.Ascii);
+        Assert.NotEmpty(matches);
+
+        return matches[0].Address;
+    }
+
+    [LinuxFact]
+    public void ListsRegions()
+    {
+        using var process = RsProcess.Attach(Pid);
+
+        var regions = process.Regions();
+
+        Assert.NotEmpty(regions);
+        Assert.All(regions, region => Assert.True(region.Size > 0));
+    }
+
+    [LinuxFact]
+    public void FindsAndReadsMarker()
+    {
+        using var process = RsProcess.Attach(Pid);
+
+        var address = FindMarker(process);
+
+        Assert.Equal(_marker, Encoding.ASCII.GetString(process.Read(address, _marker.Length)));
+    }
+
+    [LinuxFact]
+    public void PatternMatchesString()
+    {
+        using var process = RsProcess.Attach(Pid);
+
+        var pattern = string.Join(' ', Encoding.ASCII.GetBytes(_marker).Select(b => b.ToString("X2")));
+
+        Assert.Contains(FindMarker(process), process.FindPattern(pattern));
+    }
+
+    [LinuxFact]
+    public void WritesMemory()
+    {
+        using var process = RsProcess.Attach(Pid);
+        var address = FindMarker(process);
+
+        var result = process.Write(address, "RSMEM"u8);
+
+        Assert.Equal((nuint)5, result.Written);
+        Assert.Equal("RSMEM", Encoding.ASCII.GetString(process.Read(address, 5)));
+        Assert.Equal(0x4D534D53u, process.Read<uint>(address + 1));
+    }
+
+    [LinuxFact]
+    public void RestoresPatchesOnDispose()
+    {
+        nuint address;
+        using (var process = RsProcess.Attach(Pid))
+        {
+            address = FindMarker(process);
+            process.ApplyPatch(address, "patched"u8, Encoding.ASCII.GetBytes("rsmem-m"));
+
+            Assert.Single(process.Patches());
+            Assert.Equal("patched", Encoding.ASCII.GetString(process.Read(address, 7)));
+        }
+
+        using var reattached = RsProcess.Attach(Pid);
+        Assert.Equal(_marker, Encoding.ASCII.GetString(reattached.Read(address, _marker.Length)));
+    }
+
+    [LinuxFact]
+    public void RejectsSecondAttach()
+    {
+        using var process = RsProcess.Attach(Pid);
+
+        Assert.Throws<RsMemException>(() => RsProcess.Attach(Pid));
+    }
+
+    [LinuxFact]
+    public void RejectsPatchOnMismatch()
+    {
+        using var process = RsProcess.Attach(Pid);
+        var address = FindMarker(process);
+
+        Assert.Throws<RsMemException>(() => process.ApplyPatch(address, "patched"u8, "nope"u8));
+    }
+}
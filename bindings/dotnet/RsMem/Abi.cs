@@ -0,0 +1,50 @@
+using System.Runtime.CompilerServices;
+using RsMem.Interop;
+
+namespace RsMem;
+
+/// <summary>Checks that the loaded library matches the structs this binding was built against.</summary>
+public static unsafe class Abi
+{
+    /// <summary>The <c>RSMEM_ABI_VERSION</c> of <c>include/rsmem.h</c> the binding mirrors.</summary>
+    public const uint Version = 2;
+
+    private static readonly Lazy<bool> Verified = new(() =>
+    {
+        Handshake();
+        return true;
+    });
+
+    /// <summary>Host side sizes, indexed by <see cref="AbiStruct"/>.</summary>
+    public static nuint[] StructSizes() =>
+    [
+        (nuint)Unsafe.SizeOf<CallResult>(),
+        (nuint)Unsafe.SizeOf<ByteBuffer>(),
+        (nuint)Unsafe.SizeOf<CMemoryPageInfo>(),
+        (nuint)Unsafe.SizeOf<PageFilter>(),
+        (nuint)Unsafe.SizeOf<CWriteResult>(),
+        (nuint)Unsafe.SizeOf<CInstruction>(),
+        (nuint)Unsafe.SizeOf<CPatchInfo>(),
+        (nuint)Unsafe.SizeOf<CCapture>(),
+        (nuint)Unsafe.SizeOf<CCaptureMatch>(),
+        (nuint)Unsafe.SizeOf<CResolvedMatch>(),
+        (nuint)Unsafe.SizeOf<CStringMatch>(),
+        (nuint)Unsafe.SizeOf<CRegexMatch>(),
+        (nuint)Unsafe.SizeOf<CFloatQuery>(),
+        (nuint)Unsafe.SizeOf<CFloatMatch>(),
+        (nuint)Unsafe.SizeOf<CGuessedSlot>(),
+    ];
+
+    /// <summary>Throws an <see cref="RsMemException"/> when the version or a struct layout differs.</summary>
+    public static void Handshake()
+    {
+        var sizes = StructSizes();
+        fixed (nuint* ptr = sizes)
+        {
+            Results.Unwrap(NativeMethods.rsmem_abi_handshake(Version, ptr, (nuint)sizes.Length));
+        }
+    }
+
+    /// <summary><see cref="Handshake"/>, only performed once per process.</summary>
+    public static void EnsureCompatible() => _ = Verified.Value;
+}
@@ -0,0 +1,212 @@
+using System.Runtime.InteropServices;
+
+namespace RsMem.Interop;
+
+/// <summary>
+/// Raw exports of the native library, one to one with <c>include/rsmem.h</c>. Results holding
+/// buffers or strings have to be released with <see cref="free_buffer"/> / <see cref="free_string"/>,
+/// <see cref="RsProcess"/> does that for you.
+/// </summary>
+public static unsafe class NativeMethods
+{
+    public const string Library = "rsmem";
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult attach(uint pid);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult detach(uint pid);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult aob_query(TargetHandle target, ByteBuffer pattern, PageFilter filter, nuint alignment);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult aob_query_resolve(TargetHandle target, ByteBuffer pattern, PageFilter filter, nuint alignment, uint bitness);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult aob_query_capture(TargetHandle target, ByteBuffer pattern, PageFilter filter, nuint alignment);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult string_scan(TargetHandle target, ByteBuffer text, TextEncoding encodings,
+        [MarshalAs(UnmanagedType.U1)] bool caseInsensitive, PageFilter filter, nuint alignment);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult regex_scan(TargetHandle target, ByteBuffer pattern, nuint maxMatchLen, nuint maxMatches, PageFilter filter, nuint alignment);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult float_scan(TargetHandle target, CFloatQuery query, PageFilter filter);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult float_scan_candidates(TargetHandle target, CFloatQuery query, ByteBuffer candidates);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult collect_pages(TargetHandle target);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult write_memory(TargetHandle target, nuint address, ByteBuffer buffer);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult write_memory_expr(TargetHandle target, ByteBuffer address, ByteBuffer buffer);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult write_memory_ex(TargetHandle target, nuint address, ByteBuffer buffer, [MarshalAs(UnmanagedType.U1)] bool force);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult write_memory_ex_expr(TargetHandle target, ByteBuffer address, ByteBuffer buffer, [MarshalAs(UnmanagedType.U1)] bool force);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern nuint write_bytes(TargetHandle target, nuint address, ByteBuffer buffer);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern nuint write_bytes_expr(TargetHandle target, ByteBuffer address, ByteBuffer buffer);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult read_bytes(TargetHandle target, nuint address, nuint size);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult read_bytes_expr(TargetHandle target, ByteBuffer address, nuint size);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern nuint read_memory(TargetHandle target, nuint address, byte* destination, nuint size);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern nuint read_memory_expr(TargetHandle target, ByteBuffer address, byte* destination, nuint size);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult allocate_memory(TargetHandle target, nuint size, uint protection, nuint nearAddress);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult allocate_memory_expr(TargetHandle target, nuint size, uint protection, ByteBuffer nearAddress);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult free_memory(TargetHandle target, nuint address, nuint size);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult free_memory_expr(TargetHandle target, ByteBuffer address, nuint size);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult protect_memory(TargetHandle target, nuint address, nuint size, uint newProtection);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult protect_memory_expr(TargetHandle target, ByteBuffer address, nuint size, uint newProtection);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult patch_apply(TargetHandle target, nuint address, ByteBuffer buffer, ByteBuffer expected);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult patch_apply_expr(TargetHandle target, ByteBuffer address, ByteBuffer buffer, ByteBuffer expected);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult patch_restore(TargetHandle target, nuint id);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult patch_restore_all(TargetHandle target);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult patch_list(TargetHandle target);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult assemble(nuint address, ByteBuffer source, nuint length, uint bitness);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult assemble_and_write(TargetHandle target, nuint address, ByteBuffer source, nuint length, uint bitness, [MarshalAs(UnmanagedType.U1)] bool force);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult assemble_and_write_expr(TargetHandle target, ByteBuffer address, ByteBuffer source, nuint length, uint bitness, [MarshalAs(UnmanagedType.U1)] bool force);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult disassemble(TargetHandle target, nuint address, nuint count, uint bitness);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult disassemble_expr(TargetHandle target, ByteBuffer address, nuint count, uint bitness);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult make_signature(TargetHandle target, nuint address, nuint maxLen, uint bitness);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult make_signature_expr(TargetHandle target, ByteBuffer address, nuint maxLen, uint bitness);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult layout_load(ByteBuffer schema, uint format);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult layout_unload(ByteBuffer id);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult read_struct(TargetHandle target, nuint address, ByteBuffer layoutId, nuint maxDepth);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult read_struct_expr(TargetHandle target, ByteBuffer address, ByteBuffer layoutId, nuint maxDepth);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult guess_struct(TargetHandle target, nuint address, nuint size, nuint pointerSize);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult guess_struct_expr(TargetHandle target, ByteBuffer address, nuint size, nuint pointerSize);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult script_run(ByteBuffer source);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult script_load(ByteBuffer path);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult table_load(ByteBuffer source, uint format);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult table_import_ct(ByteBuffer source, nuint pointerSize);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult table_save(nuint id, uint format);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult table_unload(nuint id);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult table_evaluate(TargetHandle target, nuint id);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult evaluate_expression(TargetHandle target, ByteBuffer expression, nuint pointerSize);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult symbol_register(TargetHandle target, ByteBuffer name, ByteBuffer expression, nuint pointerSize);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult symbol_lookup(TargetHandle target, ByteBuffer name);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult symbol_list(TargetHandle target);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult symbol_remove(TargetHandle target, ByteBuffer name);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult symbol_save(TargetHandle target, ByteBuffer path, uint format);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult symbol_load(TargetHandle target, ByteBuffer path, uint format);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern void set_log_level(nuint level);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern void log_to_file(nuint level);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern void free_buffer(IntPtr buffer);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern void free_string(IntPtr str);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern void free_write_result(IntPtr result);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern uint rsmem_abi_version();
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern nuint rsmem_struct_size(AbiStruct id);
+
+    [DllImport(Library, CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+    public static extern CallResult rsmem_abi_handshake(uint version, nuint* sizes, nuint count);
+}
@@ -0,0 +1,42 @@
+using System.Runtime.InteropServices;
+using System.Text;
+
+namespace RsMem.Interop;
+
+/// <summary>Unwraps <see cref="CallResult"/>s, taking ownership of what they point to.</summary>
+internal static class Results
+{
+    public static nuint Unwrap(CallResult result)
+    {
+        if (result.IsError)
+            throw RsMemException.Take(result.Error);
+
+        return result.Result;
+    }
+
+    public static NativeBuffer UnwrapBuffer(CallResult result)
+    {
+        return new NativeBuffer((IntPtr)Unwrap(result));
+    }
+
+    public static string UnwrapString(CallResult result)
+    {
+        return TakeString((IntPtr)Unwrap(result)) ?? string.Empty;
+    }
+
+    /// <summary>Copies a string of the library and frees it.</summary>
+    public static string? TakeString(IntPtr str)
+    {
+        var value = Marshal.PtrToStringUTF8(str);
+        NativeMethods.free_string(str);
+        return value;
+    }
+
+    /// <summary>Nul terminated UTF-8, string inputs are read up to the terminator.</summary>
+    public static byte[] Utf8(string value)
+    {
+        var bytes = new byte[Encoding.UTF8.GetByteCount(value) + 1];
+        Encoding.UTF8.GetBytes(value, bytes);
+        return bytes;
+    }
+}
@@ -0,0 +1,69 @@
+using System.Runtime.InteropServices;
+
+namespace RsMem.Interop;
+
+/// <summary>
+/// Index of an attached process in the library's target table. Releasing it detaches the
+/// process, which restores the patches applied to it; handles of other processes stay valid.
+/// </summary>
+public sealed class TargetHandle : SafeHandle
+{
+    private static readonly IntPtr Invalid = new(-1);
+
+    public uint ProcessId { get; }
+
+    internal TargetHandle(nuint handle, uint processId) : base(Invalid, true)
+    {
+        ProcessId = processId;
+        SetHandle((IntPtr)handle);
+    }
+
+    public override bool IsInvalid => handle == Invalid;
+
+    protected override bool ReleaseHandle()
+    {
+        var result = NativeMethods.detach(ProcessId);
+        NativeMethods.free_string(result.Error);
+        return !result.IsError;
+    }
+}
+
+/// <summary>
+/// A <see cref="ByteBuffer"/> returned by the library. Spans handed out by <see cref="AsSpan{T}"/>
+/// are only valid until the buffer is disposed.
+/// </summary>
+public sealed unsafe class NativeBuffer : SafeHandle
+{
+    internal NativeBuffer(IntPtr buffer) : base(IntPtr.Zero, true)
+    {
+        SetHandle(buffer);
+    }
+
+    public override bool IsInvalid => handle == IntPtr.Zero;
+
+    public int Count => IsInvalid ? 0 : checked((int)((ByteBuffer*)handle)->Count);
+
+    public ReadOnlySpan<T> AsSpan<T>() where T : unmanaged
+    {
+        ObjectDisposedException.ThrowIf(IsClosed, this);
+        if (IsInvalid)
+            return ReadOnlySpan<T>.Empty;
+
+        var buffer = (ByteBuffer*)handle;
+        if (buffer->Count == 0)
+            return ReadOnlySpan<T>.Empty;
+
+        if (buffer->Size / buffer->Count != (nuint)sizeof(T))
+            throw new InvalidOperationException($"buffer elements are {buffer->Size / buffer->Count} bytes, {typeof(T).Name} is {sizeof(T)}");
+
+        return new ReadOnlySpan<T>((void*)buffer->Ptr, checked((int)buffer->Count));
+    }
+
+    public T[] ToArray<T>() where T : unmanaged => AsSpan<T>().ToArray();
+
+    protected override bool ReleaseHandle()
+    {
+        NativeMethods.free_buffer(handle);
+        return true;
+    }
+}
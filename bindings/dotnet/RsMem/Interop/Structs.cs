@@ -0,0 +1,230 @@
+using System.Runtime.InteropServices;
+
+namespace RsMem.Interop;
+
+// Mirrors of the #[repr(C)] structs, kept blittable so buffers can be viewed as spans. The
+// sizes are verified against the library by `Abi.Handshake`.
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CallResult
+{
+    /// <summary>The value, or a pointer to it for buffers, strings and structs.</summary>
+    public nuint Result;
+    /// <summary>Owned C string, null unless <see cref="IsError"/>.</summary>
+    public IntPtr Error;
+    private byte _err;
+
+    public readonly bool IsError => _err != 0;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct ByteBuffer
+{
+    public IntPtr Ptr;
+    public nuint Count;
+    public nuint Capacity;
+    /// <summary>Size of all elements in bytes.</summary>
+    public nuint Size;
+    /// <summary>Alignment of the element type.</summary>
+    public nuint Align;
+
+    /// <summary>A view over host memory, which has to stay pinned while the call runs.</summary>
+    public static unsafe ByteBuffer View(byte* ptr, int length)
+    {
+        return new ByteBuffer { Ptr = (IntPtr)ptr, Count = (nuint)length, Capacity = (nuint)length, Size = (nuint)length, Align = 1 };
+    }
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CMemoryPageInfo
+{
+    public nuint Base;
+    public nuint Size;
+    public uint Flags;
+    public IntPtr MemType;
+    public IntPtr MemProtect;
+    public IntPtr MemUsage;
+    public nuint AllocBase;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct PageFilter
+{
+    private byte _mapped;
+    private byte _readable;
+    private byte _writable;
+    private byte _executable;
+
+    public PageFilter() : this(true, true, true, false)
+    {
+    }
+
+    public PageFilter(bool readable, bool writable, bool executable, bool mapped = false)
+    {
+        _mapped = (byte)(mapped ? 1 : 0);
+        _readable = (byte)(readable ? 1 : 0);
+        _writable = (byte)(writable ? 1 : 0);
+        _executable = (byte)(executable ? 1 : 0);
+    }
+
+    /// <summary>Readable, writable or executable pages, same as the library's default.</summary>
+    public static PageFilter Default => new(true, true, true, false);
+
+    public readonly bool Mapped => _mapped != 0;
+    public readonly bool Readable => _readable != 0;
+    public readonly bool Writable => _writable != 0;
+    public readonly bool Executable => _executable != 0;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CWriteResult
+{
+    public nuint Written;
+    public WriteMechanism Mechanism;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public unsafe struct CInstruction
+{
+    public const int MaxLength = 16;
+
+    public nuint Address;
+    public nuint Length;
+    public fixed byte Bytes[MaxLength];
+    public IntPtr Mnemonic;
+    public IntPtr Operands;
+    public nuint BranchTarget;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CPatchInfo
+{
+    public nuint Id;
+    public nuint Address;
+    public nuint Size;
+    /// <summary>Owned <see cref="ByteBuffer"/>.</summary>
+    public IntPtr Original;
+    /// <summary>Owned <see cref="ByteBuffer"/>.</summary>
+    public IntPtr Patched;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CCapture
+{
+    public IntPtr Name;
+    public nuint Offset;
+    public nuint Size;
+    public ulong Value;
+    private byte _resolved;
+
+    public readonly bool Resolved => _resolved != 0;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CCaptureMatch
+{
+    public nuint Address;
+    /// <summary>Owned <see cref="ByteBuffer"/> of <see cref="CCapture"/>s.</summary>
+    public IntPtr Captures;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CResolvedMatch
+{
+    public nuint Address;
+    public nuint Target;
+    private byte _resolved;
+
+    public readonly bool Resolved => _resolved != 0;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CStringMatch
+{
+    public nuint Address;
+    public TextEncoding Encoding;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CRegexMatch
+{
+    public nuint Address;
+    public nuint Length;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CFloatQuery
+{
+    /// <summary>4 for float, 8 for double.</summary>
+    public uint ValueType;
+    /// <summary>0 rounded to <see cref="Decimals"/>, 1 truncated, 2 within <see cref="Epsilon"/>.</summary>
+    public uint Mode;
+    public double Value;
+    public uint Decimals;
+    public double Epsilon;
+    public nuint Alignment;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CFloatMatch
+{
+    public nuint Address;
+    public double Value;
+}
+
+[StructLayout(LayoutKind.Sequential)]
+public struct CGuessedSlot
+{
+    public nuint Offset;
+    public nuint Size;
+    public SlotKind Kind;
+    public ulong Value;
+    public IntPtr Detail;
+}
+
+[Flags]
+public enum TextEncoding : uint
+{
+    Ascii = 1,
+    Utf8 = 2,
+    Utf16Le = 4,
+    All = Ascii | Utf8 | Utf16Le,
+}
+
+public enum WriteMechanism : uint
+{
+    Direct = 1,
+    ProcMem = 2,
+    Reprotect = 3,
+}
+
+public enum SlotKind : uint
+{
+    Unknown = 0,
+    Zero = 1,
+    ModulePointer = 2,
+    HeapPointer = 3,
+    StringPointer = 4,
+    Float = 5,
+    SmallInt = 6,
+}
+
+/// <summary>Ids of the structs checked by the handshake, same order as the library's.</summary>
+public enum AbiStruct : uint
+{
+    CallResult = 0,
+    ByteBuffer = 1,
+    CMemoryPageInfo = 2,
+    PageFilter = 3,
+    CWriteResult = 4,
+    CInstruction = 5,
+    CPatchInfo = 6,
+    CCapture = 7,
+    CCaptureMatch = 8,
+    CResolvedMatch = 9,
+    CStringMatch = 10,
+    CRegexMatch = 11,
+    CFloatQuery = 12,
+    CFloatMatch = 13,
+    CGuessedSlot = 14,
+}
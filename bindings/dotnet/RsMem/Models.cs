@@ -0,0 +1,40 @@
+using RsMem.Interop;
+
+namespace RsMem;
+
+/// <summary>A page of the target's memory map.</summary>
+/// <param name="Protection"><c>PAGE_*</c> protection flags.</param>
+/// <param name="Usage">The module or mapping the page belongs to, when known.</param>
+public sealed record MemoryRegion(nuint Base, nuint Size, uint Protection, string Type, string ProtectionText, string? Usage, nuint AllocationBase)
+{
+    public nuint End => Base + Size;
+
+    public bool Contains(nuint address) => Base <= address && address < End;
+}
+
+public readonly record struct StringMatch(nuint Address, TextEncoding Encoding);
+
+public readonly record struct RegexMatch(nuint Address, nuint Length);
+
+public readonly record struct FloatMatch(nuint Address, double Value);
+
+/// <param name="Target">Null when the group could not be resolved.</param>
+public readonly record struct ResolvedMatch(nuint Address, nuint? Target);
+
+public readonly record struct WriteResult(nuint Written, WriteMechanism Mechanism);
+
+/// <param name="BranchTarget">Null for instructions without a near branch operand.</param>
+public sealed record Instruction(nuint Address, byte[] Bytes, string Mnemonic, string Operands, nuint? BranchTarget);
+
+public sealed record PatchInfo(nuint Id, nuint Address, byte[] Original, byte[] Patched);
+
+/// <summary>How <see cref="RsProcess.FindFloat"/> compares values.</summary>
+public enum FloatMode : uint
+{
+    /// <summary>Equal once rounded to the given decimals (at most 15).</summary>
+    Rounded = 0,
+    /// <summary>Equal once truncated to the given decimals (at most 15).</summary>
+    Truncated = 1,
+    /// <summary>Within the given epsilon.</summary>
+    Epsilon = 2,
+}
@@ -0,0 +1,20 @@
+using System.Runtime.InteropServices;
+using RsMem.Interop;
+
+namespace RsMem;
+
+/// <summary>An error reported by the native library, with its (localized) message.</summary>
+public class RsMemException : Exception
+{
+    public RsMemException(string message) : base(message)
+    {
+    }
+
+    /// <summary>Builds the exception from an error string of the library and frees the string.</summary>
+    internal static RsMemException Take(IntPtr error)
+    {
+        var message = Marshal.PtrToStringUTF8(error) ?? "unknown error";
+        NativeMethods.free_string(error);
+        return new RsMemException(message);
+    }
+}
@@ -0,0 +1,323 @@
+using RsMem.Interop;
+
+namespace RsMem;
+
+/// <summary>
+/// An attached process. Disposing it detaches the process and restores the patches applied
+/// through it. Addresses and sizes are native sized, like in the library.
+/// </summary>
+public sealed unsafe class RsProcess : IDisposable
+{
+    public TargetHandle Handle { get; }
+
+    public uint ProcessId => Handle.ProcessId;
+
+    private RsProcess(TargetHandle handle)
+    {
+        Handle = handle;
+    }
+
+    /// <summary>Attaches the process, checking the library's ABI on first use.</summary>
+    public static RsProcess Attach(uint pid)
+    {
+        Abi.EnsureCompatible();
+
+        var handle = Results.Unwrap(NativeMethods.attach(pid));
+        return new RsProcess(new TargetHandle(handle, pid));
+    }
+
+    public IReadOnlyList<MemoryRegion> Regions()
+    {
+        using var buffer = Results.UnwrapBuffer(NativeMethods.collect_pages(Handle));
+
+        var regions = new List<MemoryRegion>(buffer.Count);
+        foreach (var page in buffer.AsSpan<CMemoryPageInfo>())
+        {
+            regions.Add(new MemoryRegion(
+                page.Base,
+                page.Size,
+                page.Flags,
+                Results.TakeString(page.MemType) ?? string.Empty,
+                Results.TakeString(page.MemProtect) ?? string.Empty,
+                Results.TakeString(page.MemUsage),
+                page.AllocBase));
+        }
+
+        return regions;
+    }
+
+    /// <summary>Reads up to <paramref name="size"/> bytes, shorter when the range is partially unreadable.</summary>
+    public byte[] Read(nuint address, int size)
+    {
+        using var buffer = Results.UnwrapBuffer(NativeMethods.read_bytes(Handle, address, (nuint)size));
+        return buffer.ToArray<byte>();
+    }
+
+    public T Read<T>(nuint address) where T : unmanaged
+    {
+        T value;
+        if (NativeMethods.read_memory(Handle, address, (byte*)&value, (nuint)sizeof(T)) != (nuint)sizeof(T))
+            throw new RsMemException($"failed to read {sizeof(T)} bytes at 0x{address:X}");
+
+        return value;
+    }
+
+    /// <summary>
+    /// Writes <paramref name="data"/>, with <paramref name="force"/> read-only and executable pages
+    /// are written too.
+    /// </summary>
+    public WriteResult Write(nuint address, ReadOnlySpan<byte> data, bool force = false)
+    {
+        if (data.IsEmpty)
+            return new WriteResult(0, WriteMechanism.Direct);
+
+        IntPtr result;
+        fixed (byte* ptr = data)
+        {
+            result = (IntPtr)Results.Unwrap(NativeMethods.write_memory_ex(Handle, address, ByteBuffer.View(ptr, data.Length), force));
+        }
+
+        var write = *(CWriteResult*)result;
+        NativeMethods.free_write_result(result);
+        return new WriteResult(write.Written, write.Mechanism);
+    }
+
+    public WriteResult Write<T>(nuint address, T value, bool force = false) where T : unmanaged
+    {
+        return Write(address, new ReadOnlySpan<byte>(&value, sizeof(T)), force);
+    }
+
+    /// <summary>Addresses matching an AOB pattern, e.g. <c>48 8B 05 ?? ?? ?? ??</c>.</summary>
+    public nuint[] FindPattern(string pattern, PageFilter? filter = null, nuint alignment = 0)
+    {
+        var patternBytes = Results.Utf8(pattern);
+        fixed (byte* ptr = patternBytes)
+        {
+            var query = ByteBuffer.View(ptr, patternBytes.Length - 1);
+            using var buffer = Results.UnwrapBuffer(NativeMethods.aob_query(Handle, query, filter ?? PageFilter.Default, alignment));
+            return buffer.ToArray<nuint>();
+        }
+    }
+
+    /// <summary><see cref="FindPattern"/> resolving the first <c>( .. )</c> group into the address it refers to.</summary>
+    public ResolvedMatch[] FindPatternResolved(string pattern, uint bitness = 0, PageFilter? filter = null, nuint alignment = 0)
+    {
+        var patternBytes = Results.Utf8(pattern);
+        fixed (byte* ptr = patternBytes)
+        {
+            var query = ByteBuffer.View(ptr, patternBytes.Length - 1);
+            using var buffer = Results.UnwrapBuffer(NativeMethods.aob_query_resolve(Handle, query, filter ?? PageFilter.Default, alignment, bitness));
+
+            var matches = new List<ResolvedMatch>(buffer.Count);
+            foreach (var match in buffer.AsSpan<CResolvedMatch>())
+                matches.Add(new ResolvedMatch(match.Address, match.Resolved ? match.Target : null));
+
+            return matches.ToArray();
+        }
+    }
+
+    public StringMatch[] FindString(string text, TextEncoding encodings = TextEncoding.All, bool caseInsensitive = false, PageFilter? filter = null, nuint alignment = 0)
+    {
+        var textBytes = Results.Utf8(text);
+        fixed (byte* ptr = textBytes)
+        {
+            var query = ByteBuffer.View(ptr, textBytes.Length - 1);
+            using var buffer = Results.UnwrapBuffer(NativeMethods.string_scan(Handle, query, encodings, caseInsensitive, filter ?? PageFilter.Default, alignment));
+
+            var matches = new List<StringMatch>(buffer.Count);
+            foreach (var match in buffer.AsSpan<CStringMatch>())
+                matches.Add(new StringMatch(match.Address, match.Encoding));
+
+            return matches.ToArray();
+        }
+    }
+
+    /// <summary>Runs a bytes regex, 0 picks the library's default for the caps.</summary>
+    public RegexMatch[] FindRegex(string pattern, nuint maxMatchLength = 0, nuint maxMatches = 0, PageFilter? filter = null, nuint alignment = 0)
+    {
+        var patternBytes = Results.Utf8(pattern);
+        fixed (byte* ptr = patternBytes)
+        {
+            var query = ByteBuffer.View(ptr, patternBytes.Length - 1);
+            using var buffer = Results.UnwrapBuffer(NativeMethods.regex_scan(Handle, query, maxMatchLength, maxMatches, filter ?? PageFilter.Default, alignment));
+
+            var matches = new List<RegexMatch>(buffer.Count);
+            foreach (var match in buffer.AsSpan<CRegexMatch>())
+                matches.Add(new RegexMatch(match.Address, match.Length));
+
+            return matches.ToArray();
+        }
+    }
+
+    /// <summary>
+    /// Searches float (or double) values equal to <paramref name="value"/> under <paramref name="mode"/>,
+    /// only re-checking <paramref name="candidates"/> when given.
+    /// </summary>
+    public FloatMatch[] FindFloat(double value, bool isDouble = false, FloatMode mode = FloatMode.Rounded, uint decimals = 0, double epsilon = 0,
+        IReadOnlyList<nuint>? candidates = null, PageFilter? filter = null, nuint alignment = 0)
+    {
+        var query = new CFloatQuery
+        {
+            ValueType = isDouble ? 8u : 4u,
+            Mode = (uint)mode,
+            Value = value,
+            Decimals = decimals,
+            Epsilon = epsilon,
+            Alignment = alignment,
+        };
+
+        CallResult result;
+        if (candidates is null)
+        {
+            result = NativeMethods.float_scan(Handle, query, filter ?? PageFilter.Default);
+        }
+        else
+        {
+            var addresses = candidates.ToArray();
+            if (addresses.Length == 0)
+                return [];
+
+            fixed (nuint* ptr = addresses)
+            {
+                var list = new ByteBuffer { Ptr = (IntPtr)ptr, Count = (nuint)addresses.Length, Capacity = (nuint)addresses.Length, Size = (nuint)(addresses.Length * sizeof(nuint)), Align = (nuint)sizeof(nuint) };
+                result = NativeMethods.float_scan_candidates(Handle, query, list);
+            }
+        }
+
+        using var buffer = Results.UnwrapBuffer(result);
+
+        var matches = new List<FloatMatch>(buffer.Count);
+        foreach (var match in buffer.AsSpan<CFloatMatch>())
+            matches.Add(new FloatMatch(match.Address, match.Value));
+
+        return matches.ToArray();
+    }
+
+    public nuint Allocate(nuint size, uint protection, nuint nearAddress = 0)
+    {
+        return Results.Unwrap(NativeMethods.allocate_memory(Handle, size, protection, nearAddress));
+    }
+
+    public void Free(nuint address, nuint size)
+    {
+        Results.Unwrap(NativeMethods.free_memory(Handle, address, size));
+    }
+
+    /// <summary>Returns the previous protection.</summary>
+    public uint Protect(nuint address, nuint size, uint protection)
+    {
+        return (uint)Results.Unwrap(NativeMethods.protect_memory(Handle, address, size, protection));
+    }
+
+    /// <summary>
+    /// Writes <paramref name="bytes"/> remembering the original ones, read-only and executable pages
+    /// included. Refused when the current bytes differ from a non empty <paramref name="expected"/>,
+    /// an empty <paramref name="expected"/> skips the check.
+    /// </summary>
+    public nuint ApplyPatch(nuint address, ReadOnlySpan<byte> bytes, ReadOnlySpan<byte> expected = default)
+    {
+        if (bytes.IsEmpty)
+            throw new ArgumentException("a patch needs at least one byte", nameof(bytes));
+
+        fixed (byte* ptr = bytes)
+        fixed (byte* expectedPtr = expected)
+        {
+            return Results.Unwrap(NativeMethods.patch_apply(Handle, address, ByteBuffer.View(ptr, bytes.Length), ByteBuffer.View(expectedPtr, expected.Length)));
+        }
+    }
+
+    public void RestorePatch(nuint id)
+    {
+        Results.Unwrap(NativeMethods.patch_restore(Handle, id));
+    }
+
+    /// <summary>Returns how many patches were restored.</summary>
+    public nuint RestoreAllPatches()
+    {
+        return Results.Unwrap(NativeMethods.patch_restore_all(Handle));
+    }
+
+    public IReadOnlyList<PatchInfo> Patches()
+    {
+        using var buffer = Results.UnwrapBuffer(NativeMethods.patch_list(Handle));
+
+        var patches = new List<PatchInfo>(buffer.Count);
+        foreach (var patch in buffer.AsSpan<CPatchInfo>())
+        {
+            using var original = new NativeBuffer(patch.Original);
+            using var patched = new NativeBuffer(patch.Patched);
+            patches.Add(new PatchInfo(patch.Id, patch.Address, original.ToArray<byte>(), patched.ToArray<byte>()));
+        }
+
+        return patches;
+    }
+
+    /// <summary>Disassembles <paramref name="count"/> instructions, bitness is 16, 32 or 64, 0 picks the library's.</summary>
+    public IReadOnlyList<Instruction> Disassemble(nuint address, nuint count, uint bitness = 0)
+    {
+        using var buffer = Results.UnwrapBuffer(NativeMethods.disassemble(Handle, address, count, bitness));
+
+        var instructions = new List<Instruction>(buffer.Count);
+        var span = buffer.AsSpan<CInstruction>();
+        for (var i = 0; i < span.Length; i++)
+        {
+            // a mutable copy, fixed buffers of readonly locals can't be addressed
+            var instruction = span[i];
+            var length = (int)Math.Min(instruction.Length, (nuint)CInstruction.MaxLength);
+            var bytes = new ReadOnlySpan<byte>(instruction.Bytes, length).ToArray();
+
+            instructions.Add(new Instruction(
+                instruction.Address,
+                bytes,
+                Results.TakeString(instruction.Mnemonic) ?? string.Empty,
+                Results.TakeString(instruction.Operands) ?? string.Empty,
+                instruction.BranchTarget == 0 ? null : instruction.BranchTarget));
+        }
+
+        return instructions;
+    }
+
+    /// <summary>
+    /// Assembles intel syntax <paramref name="source"/> for <paramref name="address"/>, NOP padded up to
+    /// <paramref name="length"/> when it is not 0, and writes it. Returns the written byte count.
+    /// </summary>
+    public nuint AssembleAndWrite(nuint address, string source, nuint length = 0, uint bitness = 0, bool force = false)
+    {
+        var sourceBytes = Results.Utf8(source);
+        fixed (byte* ptr = sourceBytes)
+        {
+            return Results.Unwrap(NativeMethods.assemble_and_write(Handle, address, ByteBuffer.View(ptr, sourceBytes.Length - 1), length, bitness, force));
+        }
+    }
+
+    /// <summary>A pattern of at most <paramref name="maxLength"/> bytes unique within the module of the address.</summary>
+    public string MakeSignature(nuint address, nuint maxLength = 64, uint bitness = 0)
+    {
+        return Results.UnwrapString(NativeMethods.make_signature(Handle, address, maxLength, bitness));
+    }
+
+    /// <summary>Decodes the struct at <paramref name="address"/> with a registered layout, as json.</summary>
+    public string ReadStruct(nuint address, string layoutId, nuint maxDepth = 1)
+    {
+        var layoutIdBytes = Results.Utf8(layoutId);
+        fixed (byte* ptr = layoutIdBytes)
+        {
+            return Results.UnwrapString(NativeMethods.read_struct(Handle, address, ByteBuffer.View(ptr, layoutIdBytes.Length - 1), maxDepth));
+        }
+    }
+
+    /// <summary>Registers the layouts of a json or toml schema, returns how many were registered.</summary>
+    public static nuint LoadLayouts(string schema, bool toml = false)
+    {
+        var schemaBytes = Results.Utf8(schema);
+        fixed (byte* ptr = schemaBytes)
+        {
+            return Results.Unwrap(NativeMethods.layout_load(ByteBuffer.View(ptr, schemaBytes.Length - 1), toml ? 1u : 0u));
+        }
+    }
+
+    public void Dispose()
+    {
+        Handle.Dispose();
+    }
+}
@@ -0,0 +1,26 @@
+using RsMem.Interop;
+
+namespace RsMem;
+
+/// <summary>Runs Lua scripts with the library's <c>rsmem</c> module loaded.</summary>
+public static unsafe class Script
+{
+    /// <summary>Returns what the script printed, throws with the Lua error and traceback when it fails.</summary>
+    public static string Run(string source)
+    {
+        var bytes = Results.Utf8(source);
+        fixed (byte* ptr = bytes)
+        {
+            return Results.UnwrapString(NativeMethods.script_run(ByteBuffer.View(ptr, bytes.Length - 1)));
+        }
+    }
+
+    public static string Load(string path)
+    {
+        var bytes = Results.Utf8(path);
+        fixed (byte* ptr = bytes)
+        {
+            return Results.UnwrapString(NativeMethods.script_load(ByteBuffer.View(ptr, bytes.Length - 1)));
+        }
+    }
+}
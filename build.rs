@@ -0,0 +1,21 @@
+fn main() {
+    #[cfg(feature = "node")]
+    napi_build::setup();
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+        .expect("failed to read cbindgen.toml");
+
+    // builds never touch the source tree, `cargo xtask header` refreshes the committed copy.
+    // the committed header is still usable when generation fails, don't break the build over it
+    match cbindgen::Builder::new().with_crate(&crate_dir).with_config(config).generate() {
+        Ok(bindings) => {
+            bindings.write_to_file(format!("{}/rsmem.h", out_dir));
+        },
+        Err(err) => println!("cargo:warning=failed to generate rsmem.h: {}", err),
+    }
+}
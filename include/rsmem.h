@@ -0,0 +1,634 @@
+#ifndef RSMEM_H
+#define RSMEM_H
+
+/* Generated from the crate sources by `cargo xtask header` (cbindgen), do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Bumped whenever an exported signature or a `#[repr(C)]` struct changes.
+#define RSMEM_ABI_VERSION 2
+
+// Structs shared with hosts, in the order `rsmem_abi_handshake` expects their sizes.
+enum AbiStruct
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  AbiStructCallResult = 0,
+  AbiStructByteBuffer = 1,
+  AbiStructCMemoryPageInfo = 2,
+  AbiStructPageFilter = 3,
+  AbiStructCWriteResult = 4,
+  AbiStructCInstruction = 5,
+  AbiStructCPatchInfo = 6,
+  AbiStructCCapture = 7,
+  AbiStructCCaptureMatch = 8,
+  AbiStructCResolvedMatch = 9,
+  AbiStructCStringMatch = 10,
+  AbiStructCRegexMatch = 11,
+  AbiStructCFloatQuery = 12,
+  AbiStructCFloatMatch = 13,
+  AbiStructCGuessedSlot = 14,
+};
+#ifndef __cplusplus
+typedef uint32_t AbiStruct;
+#endif // __cplusplus
+
+enum SlotKind
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  SlotKindUnknown = 0,
+  SlotKindZero = 1,
+  SlotKindModulePointer = 2,
+  SlotKindHeapPointer = 3,
+  SlotKindStringPointer = 4,
+  SlotKindFloat = 5,
+  SlotKindSmallInt = 6,
+};
+#ifndef __cplusplus
+typedef uint32_t SlotKind;
+#endif // __cplusplus
+
+enum TextEncoding
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  TextEncodingAscii = 1,
+  TextEncodingUtf8 = 2,
+  TextEncodingUtf16Le = 4,
+};
+#ifndef __cplusplus
+typedef uint32_t TextEncoding;
+#endif // __cplusplus
+
+enum WriteMechanism
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  WriteMechanismDirect = 1,
+  WriteMechanismProcMem = 2,
+  WriteMechanismReprotect = 3,
+};
+#ifndef __cplusplus
+typedef uint32_t WriteMechanism;
+#endif // __cplusplus
+
+typedef struct CallResultUsize {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultUsize;
+
+typedef struct CallResultBool {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultBool;
+
+typedef struct CallResultVecUsize {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecUsize;
+
+typedef uintptr_t TargetHandle;
+
+typedef struct ByteBuffer {
+  uint8_t *ptr;
+  uintptr_t count;
+  uintptr_t capacity;
+  uintptr_t size;
+  // alignment of the elements, `free` needs it to rebuild the allocation layout
+  uintptr_t align;
+} ByteBuffer;
+
+typedef struct PageFilter {
+  bool mapped;
+  bool readable;
+  bool writable;
+  bool executable;
+} PageFilter;
+
+typedef struct CallResultVecCResolvedMatch {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCResolvedMatch;
+
+typedef struct CallResultVecCCaptureMatch {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCCaptureMatch;
+
+typedef struct CallResultVecCStringMatch {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCStringMatch;
+
+typedef struct CallResultVecCRegexMatch {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCRegexMatch;
+
+typedef struct CallResultVecCFloatMatch {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCFloatMatch;
+
+typedef struct CFloatQuery {
+  // 4 for f32, 8 for f64
+  uint32_t value_type;
+  // 0 rounded to `decimals`, 1 truncated to `decimals`, 2 within `epsilon`
+  uint32_t mode;
+  double value;
+  // at most 15
+  uint32_t decimals;
+  double epsilon;
+  // 0 for the natural alignment of the type
+  uintptr_t alignment;
+} CFloatQuery;
+
+typedef struct CallResultVecCMemoryPageInfo {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCMemoryPageInfo;
+
+typedef struct CallResultCWriteResult {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultCWriteResult;
+
+typedef struct CallResultVecU8 {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecU8;
+
+typedef struct CallResultU32 {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultU32;
+
+typedef struct CallResultPatchId {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultPatchId;
+
+typedef uintptr_t PatchId;
+
+typedef struct CallResultVecCPatchInfo {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCPatchInfo;
+
+typedef struct CallResultVecCInstruction {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCInstruction;
+
+typedef struct CallResultCChar {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultCChar;
+
+typedef struct CallResultVecCGuessedSlot {
+  uintptr_t result;
+  const char *error;
+  bool _err;
+} CallResultVecCGuessedSlot;
+
+typedef struct CWriteResult {
+  uintptr_t written;
+  WriteMechanism mechanism;
+} CWriteResult;
+
+typedef struct CMemoryPageInfo {
+  uintptr_t base;
+  uintptr_t size;
+  uint32_t flags;
+  const int8_t *mem_type;
+  const int8_t *mem_protect;
+  const int8_t *mem_usage;
+  uintptr_t alloc_base;
+} CMemoryPageInfo;
+
+typedef struct CInstruction {
+  uintptr_t address;
+  uintptr_t length;
+  uint8_t bytes[16];
+  const char *mnemonic;
+  const char *operands;
+  // 0 for instructions without a near branch operand
+  uintptr_t branch_target;
+} CInstruction;
+
+typedef struct CPatchInfo {
+  PatchId id;
+  uintptr_t address;
+  uintptr_t size;
+  struct ByteBuffer *original;
+  struct ByteBuffer *patched;
+} CPatchInfo;
+
+typedef struct CCapture {
+  // null for unnamed groups
+  const char *name;
+  uintptr_t offset;
+  uintptr_t size;
+  // zero extended little-endian value, only meaningful when `resolved` is set
+  uint64_t value;
+  // false when the group could not be decoded, `value` is 0 then
+  bool resolved;
+} CCapture;
+
+typedef struct CCaptureMatch {
+  uintptr_t address;
+  // `CCapture`s in the order the groups appear in the pattern
+  struct ByteBuffer *captures;
+} CCaptureMatch;
+
+typedef struct CResolvedMatch {
+  uintptr_t address;
+  // only meaningful when `resolved` is set
+  uintptr_t target;
+  // false when the group could not be resolved, e.g. it is not an operand of a decodable
+  // instruction, `target` is 0 then
+  bool resolved;
+} CResolvedMatch;
+
+typedef struct CStringMatch {
+  uintptr_t address;
+  TextEncoding encoding;
+} CStringMatch;
+
+typedef struct CRegexMatch {
+  uintptr_t address;
+  uintptr_t length;
+} CRegexMatch;
+
+typedef struct CFloatMatch {
+  uintptr_t address;
+  double value;
+} CFloatMatch;
+
+typedef struct CGuessedSlot {
+  uintptr_t offset;
+  uintptr_t size;
+  SlotKind kind;
+  // the raw little-endian slot value
+  uint64_t value;
+  // null unless the slot is a module or string pointer
+  const char *detail;
+} CGuessedSlot;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+struct CallResultUsize attach(uint32_t pid);
+
+struct CallResultBool detach(uint32_t pid);
+
+// Searches an AOB pattern (`48 8B 05 ?? ?? ?? ??`) over the pages matching `filter`.
+struct CallResultVecUsize aob_query(TargetHandle target_handle,
+                                    struct ByteBuffer pattern_buffer,
+                                    struct PageFilter filter,
+                                    uintptr_t alignment);
+
+// `aob_query` that also resolves the first `( .. )` group of the pattern for every match, e.g.
+// `48 8B 05 (?? ?? ?? ??)` yields the global the rip relative operand points to. Matches whose
+// group can't be resolved are kept with `resolved` unset.
+struct CallResultVecCResolvedMatch aob_query_resolve(TargetHandle target_handle,
+                                                     struct ByteBuffer pattern_buffer,
+                                                     struct PageFilter filter,
+                                                     uintptr_t alignment,
+                                                     uint32_t bitness);
+
+// `aob_query` returning, for every match, the value of each `( .. )` / `(name: .. )` group
+// decoded as a little-endian integer of the group width (1 to 8 bytes).
+struct CallResultVecCCaptureMatch aob_query_capture(TargetHandle target_handle,
+                                                    struct ByteBuffer pattern_buffer,
+                                                    struct PageFilter filter,
+                                                    uintptr_t alignment);
+
+// Searches `text` in every encoding flagged in `encodings` (1 ascii, 2 utf-8, 4 utf-16le) at
+// once and returns the matches sorted by address. A match is reported for each flagged encoding
+// it is valid in, ASCII text is found as both ascii and utf-8.
+struct CallResultVecCStringMatch string_scan(TargetHandle target_handle,
+                                             struct ByteBuffer text_buffer,
+                                             uint32_t encodings,
+                                             bool case_insensitive,
+                                             struct PageFilter filter,
+                                             uintptr_t alignment);
+
+// Runs a bytes regex over the filtered pages. Longer matches than `max_match_len` are reported
+// with their length truncated to it (0 picks a default), `max_matches` keeps only the lowest
+// addresses (0 for no cap).
+struct CallResultVecCRegexMatch regex_scan(TargetHandle target_handle,
+                                           struct ByteBuffer pattern_buffer,
+                                           uintptr_t max_match_len,
+                                           uintptr_t max_matches,
+                                           struct PageFilter filter,
+                                           uintptr_t alignment);
+
+// Searches f32/f64 values matching `query` (rounded, truncated or within epsilon) over the
+// filtered pages.
+struct CallResultVecCFloatMatch float_scan(TargetHandle target_handle,
+                                           struct CFloatQuery query,
+                                           struct PageFilter filter);
+
+// `float_scan` over a host supplied list of candidate addresses (`usize`s).
+struct CallResultVecCFloatMatch float_scan_candidates(TargetHandle target_handle,
+                                                      struct CFloatQuery query,
+                                                      struct ByteBuffer candidates_buffer);
+
+struct CallResultVecCMemoryPageInfo collect_pages(TargetHandle target);
+
+struct CallResultUsize write_memory(TargetHandle target,
+                                    uintptr_t address,
+                                    struct ByteBuffer buffer_repr);
+
+// `write_memory` at an address expression, see `evaluate_expression`.
+struct CallResultUsize write_memory_expr(TargetHandle target,
+                                         struct ByteBuffer address_repr,
+                                         struct ByteBuffer buffer_repr);
+
+// `write_memory` with an opt-in `force` mode that writes to read-only and executable pages too.
+struct CallResultCWriteResult write_memory_ex(TargetHandle target,
+                                              uintptr_t address,
+                                              struct ByteBuffer buffer_repr,
+                                              bool force);
+
+// `write_memory_ex` at an address expression, see `evaluate_expression`.
+struct CallResultCWriteResult write_memory_ex_expr(TargetHandle target,
+                                                   struct ByteBuffer address_repr,
+                                                   struct ByteBuffer buffer_repr,
+                                                   bool force);
+
+uintptr_t write_bytes(TargetHandle target, uintptr_t address, struct ByteBuffer buffer_repr);
+
+// `write_bytes` at an address expression, see `evaluate_expression`. Returns 0 when it fails to
+// evaluate.
+uintptr_t write_bytes_expr(TargetHandle target,
+                           struct ByteBuffer address_repr,
+                           struct ByteBuffer buffer_repr);
+
+struct CallResultVecU8 read_bytes(TargetHandle target, uintptr_t address, uintptr_t size);
+
+// `read_bytes` at an address expression, see `evaluate_expression`.
+struct CallResultVecU8 read_bytes_expr(TargetHandle target,
+                                       struct ByteBuffer address_repr,
+                                       uintptr_t size);
+
+uintptr_t read_memory(TargetHandle target, uintptr_t address, uint8_t *destination, uintptr_t size);
+
+// `read_memory` at an address expression, see `evaluate_expression`. Returns 0 when it fails to
+// evaluate.
+uintptr_t read_memory_expr(TargetHandle target,
+                           struct ByteBuffer address_repr,
+                           uint8_t *destination,
+                           uintptr_t size);
+
+struct CallResultUsize allocate_memory(TargetHandle target,
+                                       uintptr_t size,
+                                       uint32_t protection,
+                                       uintptr_t near_address);
+
+// `allocate_memory` near an address expression, see `evaluate_expression`.
+struct CallResultUsize allocate_memory_expr(TargetHandle target,
+                                            uintptr_t size,
+                                            uint32_t protection,
+                                            struct ByteBuffer near_address_repr);
+
+struct CallResultBool free_memory(TargetHandle target, uintptr_t address, uintptr_t size);
+
+// `free_memory` at an address expression, see `evaluate_expression`.
+struct CallResultBool free_memory_expr(TargetHandle target,
+                                       struct ByteBuffer address_repr,
+                                       uintptr_t size);
+
+// Changes the protection of the pages covering the range, returns the previous protection of the
+// first page.
+struct CallResultU32 protect_memory(TargetHandle target,
+                                    uintptr_t address,
+                                    uintptr_t size,
+                                    uint32_t new_protection);
+
+// `protect_memory` at an address expression, see `evaluate_expression`.
+struct CallResultU32 protect_memory_expr(TargetHandle target,
+                                         struct ByteBuffer address_repr,
+                                         uintptr_t size,
+                                         uint32_t new_protection);
+
+// Overwrites the bytes at `address`, writing to read-only and executable pages too, and saves the
+// original bytes for `patch_restore`. The current bytes have to equal `expected` unless it is
+// empty, an empty `expected` skips the check.
+struct CallResultPatchId patch_apply(TargetHandle target,
+                                     uintptr_t address,
+                                     struct ByteBuffer buffer_repr,
+                                     struct ByteBuffer expected_repr);
+
+// `patch_apply` at an address expression, see `evaluate_expression`.
+struct CallResultPatchId patch_apply_expr(TargetHandle target,
+                                          struct ByteBuffer address_repr,
+                                          struct ByteBuffer buffer_repr,
+                                          struct ByteBuffer expected_repr);
+
+struct CallResultBool patch_restore(TargetHandle target, PatchId id);
+
+struct CallResultUsize patch_restore_all(TargetHandle target);
+
+struct CallResultVecCPatchInfo patch_list(TargetHandle target);
+
+// Assembles newline or `;` separated intel syntax instructions (`mov eax, 1; ret`) for `address`
+// without writing them, `//` starts a comment.
+struct CallResultVecU8 assemble(uintptr_t address,
+                                struct ByteBuffer source_repr,
+                                uintptr_t length,
+                                uint32_t bitness);
+
+// Assembles the instructions relative to `address`, pads them with NOPs up to `length` when it
+// is not 0 and writes them the same way `write_memory_ex` does.
+struct CallResultUsize assemble_and_write(TargetHandle target,
+                                          uintptr_t address,
+                                          struct ByteBuffer source_repr,
+                                          uintptr_t length,
+                                          uint32_t bitness,
+                                          bool force);
+
+// `assemble_and_write` at an address expression, see `evaluate_expression`.
+struct CallResultUsize assemble_and_write_expr(TargetHandle target,
+                                               struct ByteBuffer address_repr,
+                                               struct ByteBuffer source_repr,
+                                               uintptr_t length,
+                                               uint32_t bitness,
+                                               bool force);
+
+// Disassembles `count` x86 instructions at `address`. `bitness` is 16, 32 or 64, anything else
+// picks the bitness of the library.
+struct CallResultVecCInstruction disassemble(TargetHandle target,
+                                             uintptr_t address,
+                                             uintptr_t count,
+                                             uint32_t bitness);
+
+// `disassemble` at an address expression, see `evaluate_expression`.
+struct CallResultVecCInstruction disassemble_expr(TargetHandle target,
+                                                  struct ByteBuffer address_repr,
+                                                  uintptr_t count,
+                                                  uint32_t bitness);
+
+// Generates an `aob_query` pattern of at most `max_len` bytes (capped at 256) starting at `address`
+// that is unique within the module of the address. Returns the pattern as a C string.
+struct CallResultCChar make_signature(TargetHandle target,
+                                      uintptr_t address,
+                                      uintptr_t max_len,
+                                      uint32_t bitness);
+
+// `make_signature` at an address expression, see `evaluate_expression`.
+struct CallResultCChar make_signature_expr(TargetHandle target,
+                                           struct ByteBuffer address_repr,
+                                           uintptr_t max_len,
+                                           uint32_t bitness);
+
+// Registers the layouts of a json (`format` 0) or toml (`format` 1) schema. Returns the number
+// of registered layouts, fails when layouts inline each other in a cycle or a pointer `length`
+// is not 4 or 8.
+struct CallResultUsize layout_load(struct ByteBuffer schema_repr, uint32_t format);
+
+struct CallResultBool layout_unload(struct ByteBuffer id_repr);
+
+// Decodes the struct at `address` using the registered layout `layout_id`, following pointers
+// `max_depth` levels deep. Returns the decoded tree as a json C string.
+struct CallResultCChar read_struct(TargetHandle target,
+                                   uintptr_t address,
+                                   struct ByteBuffer layout_id_repr,
+                                   uintptr_t max_depth);
+
+// `read_struct` at an address expression, see `evaluate_expression`.
+struct CallResultCChar read_struct_expr(TargetHandle target,
+                                        struct ByteBuffer address_repr,
+                                        struct ByteBuffer layout_id_repr,
+                                        uintptr_t max_depth);
+
+// Classifies every 4/8 byte slot of the `size` bytes at `address` as zero, small int, float,
+// module, heap or string pointer, validating pointers against the page map. `pointer_size` is
+// the one of the target, 4 or 8, 0 picks the native size.
+struct CallResultVecCGuessedSlot guess_struct(TargetHandle target,
+                                              uintptr_t address,
+                                              uintptr_t size,
+                                              uintptr_t pointer_size);
+
+// `guess_struct` at an address expression, see `evaluate_expression`. `[..]` in the expression
+// reads `pointer_size` pointers too.
+struct CallResultVecCGuessedSlot guess_struct_expr(TargetHandle target,
+                                                   struct ByteBuffer address_repr,
+                                                   uintptr_t size,
+                                                   uintptr_t pointer_size);
+
+// Runs a Lua script with the `rsmem` module loaded. Returns what the script printed as a C
+// string, the error carries the Lua message and traceback when the script fails.
+struct CallResultCChar script_run(struct ByteBuffer source_repr);
+
+// `script_run` for the script file at `path`.
+struct CallResultCChar script_load(struct ByteBuffer path_repr);
+
+// Registers a json (`format` 0) or toml (`format` 1) cheat table. Returns the table id.
+struct CallResultUsize table_load(struct ByteBuffer source_repr, uint32_t format);
+
+// Imports a Cheat Engine `.CT` file as a table, `pointer_size` is 4 or 8 (0 for the native size).
+// Entries that can't be represented are skipped. Returns the table id.
+struct CallResultUsize table_import_ct(struct ByteBuffer source_repr, uintptr_t pointer_size);
+
+// Serializes the table `id` as json (`format` 0) or toml (`format` 1). Returns a C string.
+struct CallResultCChar table_save(uintptr_t id, uint32_t format);
+
+struct CallResultBool table_unload(uintptr_t id);
+
+// Resolves every entry of the table `id`, writes the freeze values and reads the current ones.
+// Returns a json array of `{ description, address, value, error }` as a C string, entries that
+// fail to resolve or read carry the error instead of failing the call.
+struct CallResultCChar table_evaluate(TargetHandle target, uintptr_t id);
+
+// Evaluates an address expression such as `[[game.so+1F00]+18]+40`. Bare numbers are hex, `#`
+// marks decimals, names are registered symbols or modules and `[..]` reads a `pointer_size`
+// (4 or 8, 0 for the native size) pointer.
+struct CallResultUsize evaluate_expression(TargetHandle target,
+                                           struct ByteBuffer expression_repr,
+                                           uintptr_t pointer_size);
+
+// Names the address an expression evaluates to, for use in later expressions and table entries.
+// A plain hex address works as an expression too. Returns the address.
+struct CallResultUsize symbol_register(TargetHandle target,
+                                       struct ByteBuffer name_repr,
+                                       struct ByteBuffer expression_repr,
+                                       uintptr_t pointer_size);
+
+struct CallResultUsize symbol_lookup(TargetHandle target, struct ByteBuffer name_repr);
+
+// Returns the symbols of the target as a json array of `{ name, address, expression,
+// pointer_size }` C string in registration order, addresses are `"0x.."` strings.
+struct CallResultCChar symbol_list(TargetHandle target);
+
+struct CallResultBool symbol_remove(TargetHandle target, struct ByteBuffer name_repr);
+
+// Writes the symbols of the target to `path` as json (`format` 0) or toml (`format` 1).
+struct CallResultBool symbol_save(TargetHandle target,
+                                  struct ByteBuffer path_repr,
+                                  uint32_t format);
+
+// Defines the symbols of a file written by `symbol_save`, evaluating their expressions against
+// the target in the saved order. Symbols that no longer evaluate are skipped. Returns the number
+// of defined symbols.
+struct CallResultUsize symbol_load(TargetHandle target,
+                                   struct ByteBuffer path_repr,
+                                   uint32_t format);
+
+void set_log_level(uintptr_t level);
+
+void log_to_file(uintptr_t level);
+
+// Frees a `ByteBuffer` returned by the library. Strings and buffers its elements point to are
+// not freed, release them with `free_string` / `free_buffer` first.
+void free_buffer(struct ByteBuffer *buffer);
+
+// Frees a C string returned by the library, including `CallResult::error`.
+void free_string(char *string);
+
+// Frees the result of `write_memory_ex`.
+void free_write_result(struct CWriteResult *result);
+
+// Version of the exported functions and structs, see `rsmem.h`.
+uint32_t rsmem_abi_version(void);
+
+// Size of the `AbiStruct` with the given id in this build, 0 for unknown ids.
+uintptr_t rsmem_struct_size(uint32_t id);
+
+// Checks the ABI version and the struct sizes (`count` `usize`s ordered by `AbiStruct`) a host
+// was built against, so layout mismatches surface at load time instead of as corrupted results.
+struct CallResultBool rsmem_abi_handshake(uint32_t version,
+                                          const uintptr_t *sizes,
+                                          uintptr_t count);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RSMEM_H */
@@ -0,0 +1,72 @@
+use std::mem::size_of;
+
+use crate::{
+    definitions::{ByteBuffer, CMemoryPageInfo, CWriteResult, CallResult, PageFilter},
+    disasm::CInstruction,
+    float::{CFloatMatch, CFloatQuery},
+    guess::CGuessedSlot,
+    patch::CPatchInfo,
+    pattern::{CCapture, CCaptureMatch, CResolvedMatch},
+    regex_scan::CRegexMatch,
+    text::CStringMatch,
+};
+
+/// Bumped whenever an exported signature or a `#[repr(C)]` struct changes.
+pub const RSMEM_ABI_VERSION: u32 = 2;
+
+/// Structs shared with hosts, in the order `rsmem_abi_handshake` expects their sizes.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbiStruct {
+    CallResult = 0,
+    ByteBuffer = 1,
+    CMemoryPageInfo = 2,
+    PageFilter = 3,
+    CWriteResult = 4,
+    CInstruction = 5,
+    CPatchInfo = 6,
+    CCapture = 7,
+    CCaptureMatch = 8,
+    CResolvedMatch = 9,
+    CStringMatch = 10,
+    CRegexMatch = 11,
+    CFloatQuery = 12,
+    CFloatMatch = 13,
+    CGuessedSlot = 14,
+}
+
+// indexed by `AbiStruct`
+const STRUCT_SIZES: [usize; 15] = [
+    size_of::<CallResult<usize>>(),
+    size_of::<ByteBuffer>(),
+    size_of::<CMemoryPageInfo>(),
+    size_of::<PageFilter>(),
+    size_of::<CWriteResult>(),
+    size_of::<CInstruction>(),
+    size_of::<CPatchInfo>(),
+    size_of::<CCapture>(),
+    size_of::<CCaptureMatch>(),
+    size_of::<CResolvedMatch>(),
+    size_of::<CStringMatch>(),
+    size_of::<CRegexMatch>(),
+    size_of::<CFloatQuery>(),
+    size_of::<CFloatMatch>(),
+    size_of::<CGuessedSlot>(),
+];
+
+/// Size of the struct as laid out by this build, 0 for unknown ids.
+pub fn struct_size(id: u32) -> usize {
+    STRUCT_SIZES.get(id as usize).copied().unwrap_or_default()
+}
+
+/// Returns the first struct whose host side size differs from ours. A host may know fewer
+/// structs than this build, but not more.
+pub fn find_mismatch(sizes: &[usize]) -> Option<usize> {
+    if sizes.len() > STRUCT_SIZES.len() {
+        return Some(STRUCT_SIZES.len());
+    }
+
+    sizes.iter()
+        .zip(STRUCT_SIZES.iter())
+        .position(|(host, ours)| host != ours)
+}
@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+
+use iced_x86::{Code, Decoder, DecoderOptions, Encoder, Instruction, MemoryOperand, MemorySize, Mnemonic, OpKind, Register};
+use once_cell::sync::Lazy;
+
+use crate::{disasm::{normalize_bitness, MAX_INSTRUCTION_LENGTH}, error::MemoryError};
+
+static REGISTERS: Lazy<HashMap<String, Register>> = Lazy::new(|| {
+    Register::values()
+        .filter(|register| *register != Register::None)
+        .map(|register| (format!("{:?}", register).to_lowercase(), register))
+        .collect()
+});
+
+static MNEMONICS: Lazy<HashMap<String, Vec<Code>>> = Lazy::new(|| {
+    let mut mnemonics: HashMap<String, Vec<Code>> = HashMap::new();
+    for code in Code::values().filter(|code| code.op_code().is_instruction() && code.mnemonic() != Mnemonic::INVALID) {
+        mnemonics.entry(format!("{:?}", code.mnemonic()).to_lowercase())
+            .or_default()
+            .push(code);
+    }
+
+    mnemonics
+});
+
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Register(Register),
+    Immediate(i64),
+    Memory(Memory),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Memory {
+    base: Register,
+    index: Register,
+    scale: u32,
+    displacement: i64,
+    size: Option<usize>,
+}
+
+// how a memory operand gets encoded, for rip relative ones iced expects the absolute target
+// as displacement
+#[derive(Debug, Clone, Copy)]
+struct Addressing {
+    rip_relative: bool,
+    displacement: i64,
+    length: Option<usize>,
+}
+
+impl Memory {
+    fn build(&self, addressing: Addressing) -> MemoryOperand {
+        let base = match addressing.rip_relative {
+            true => Register::RIP,
+            false => self.base,
+        };
+
+        MemoryOperand::with_base_index_scale_displ_size(base, self.index, self.scale, addressing.displacement, 1)
+    }
+
+    fn is_absolute(&self) -> bool {
+        self.base == Register::None && self.index == Register::None
+    }
+}
+
+/// Assembles newline or `;` separated intel syntax instructions as if they were placed at
+/// `address`, then pads the result with NOPs up to `length` when it is not 0. `//` starts a comment.
+pub fn assemble(source: &str, address: usize, length: usize, bitness: u32) -> Result<Vec<u8>, MemoryError> {
+    let bitness = normalize_bitness(bitness);
+    let mut bytes = Vec::new();
+
+    let statements = source.lines()
+        .flat_map(|line| line.split("//").next().unwrap_or_default().split(';'))
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty());
+
+    for statement in statements {
+        let encoded = assemble_statement(statement, address + bytes.len(), bitness)?;
+        bytes.extend(encoded);
+    }
+
+    if length > 0 {
+        if bytes.len() > length {
+            return Err(MemoryError::AssembledCodeTooLong);
+        }
+
+        bytes.resize(length, 0x90);
+    }
+
+    Ok(bytes)
+}
+
+fn assemble_statement(statement: &str, ip: usize, bitness: u32) -> Result<Vec<u8>, MemoryError> {
+    let (mnemonic, rest) = match statement.split_once(char::is_whitespace) {
+        Some((mnemonic, rest)) => (mnemonic, rest.trim()),
+        None => (statement, ""),
+    };
+
+    let operands = split_operands(rest)
+        .into_iter()
+        .map(parse_operand)
+        .collect::<Option<Vec<_>>>()
+        .ok_or(MemoryError::InvalidInstruction)?;
+
+    let codes = MNEMONICS.get(&mnemonic.to_lowercase())
+        .ok_or(MemoryError::InvalidInstruction)?;
+
+    let memory = operands.iter().find_map(|operand| match operand {
+        Operand::Memory(memory) => Some(*memory),
+        _ => None,
+    });
+
+    let addressing = match memory {
+        // in 64-bit mode `[address]` is encoded rip relative when it is in range
+        Some(memory) if bitness == 64 && memory.is_absolute() => vec![
+            Addressing { rip_relative: true, displacement: memory.displacement, length: None },
+            Addressing { rip_relative: false, displacement: memory.displacement, length: None },
+        ],
+        // `[rip + disp]` is relative to the next instruction, so guess the length until it fits
+        Some(memory) if memory.base == Register::RIP => (2..=MAX_INSTRUCTION_LENGTH)
+            .map(|length| Addressing {
+                rip_relative: true,
+                displacement: (ip + length) as i64 + memory.displacement,
+                length: Some(length),
+            })
+            .collect(),
+        Some(memory) => vec![Addressing { rip_relative: false, displacement: memory.displacement, length: None }],
+        None => vec![Addressing { rip_relative: false, displacement: 0, length: None }],
+    };
+
+    let mut candidates = Vec::new();
+    for code in codes.iter().cloned() {
+        let op_code = code.op_code();
+        let supported = match bitness {
+            64 => op_code.mode64(),
+            32 => op_code.mode32(),
+            _ => op_code.mode16(),
+        };
+
+        if !supported || op_code.op_count() as usize != operands.len() {
+            continue;
+        }
+
+        for addressing in addressing.iter().cloned() {
+            let Some(instruction) = create(code, &operands, addressing) else {
+                continue;
+            };
+
+            // `lea` and friends only compute the address, any size prefix goes
+            let memory_size = instruction.memory_size().size();
+            if let Some(Memory { size: Some(size), .. }) = memory {
+                if instruction.memory_size() != MemorySize::Unknown && memory_size != size {
+                    continue;
+                }
+            }
+
+            match encode(&instruction, &operands, ip, bitness) {
+                Some(bytes) if addressing.length.map_or(true, |length| length == bytes.len()) => {
+                    candidates.push((memory_size, bytes));
+                    break;
+                },
+                _ => {}
+            }
+        }
+    }
+
+    // `mov [rax], 1` could be any width, make the caller spell it out
+    if let Some(Memory { size: None, .. }) = memory {
+        let first = candidates.first().map(|(size, _)| *size);
+        if candidates.iter().any(|(size, _)| Some(*size) != first) {
+            return Err(MemoryError::AmbiguousOperandSize);
+        }
+    }
+
+    candidates.into_iter()
+        .map(|(_, bytes)| bytes)
+        .min_by_key(|bytes| bytes.len())
+        .ok_or(MemoryError::InvalidInstruction)
+}
+
+fn create(code: Code, operands: &[Operand], addressing: Addressing) -> Option<Instruction> {
+    use Operand::*;
+
+    let instruction = match *operands {
+        [] => Ok(Instruction::with(code)),
+        [Immediate(value)] => match Instruction::with_branch(code, value as u64) {
+            Ok(instruction) => Ok(instruction),
+            Err(_) => Instruction::with1(code, i32::try_from(value).ok()?),
+        },
+        [Register(register)] => Instruction::with1(code, register),
+        [Memory(memory)] => Instruction::with1(code, memory.build(addressing)),
+        [Register(first), Register(second)] => Instruction::with2(code, first, second),
+        [Register(register), Memory(memory)] => Instruction::with2(code, register, memory.build(addressing)),
+        [Memory(memory), Register(register)] => Instruction::with2(code, memory.build(addressing), register),
+        [Register(register), Immediate(value)] => match (i32::try_from(value), u32::try_from(value)) {
+            (Ok(value), _) => Instruction::with2(code, register, value),
+            (_, Ok(value)) => Instruction::with2(code, register, value),
+            _ => Instruction::with2(code, register, value),
+        },
+        [Memory(memory), Immediate(value)] => Instruction::with2(code, memory.build(addressing), i32::try_from(value).ok()?),
+        [Register(first), Register(second), Register(third)] => Instruction::with3(code, first, second, third),
+        [Register(first), Register(second), Immediate(value)] => Instruction::with3(code, first, second, i32::try_from(value).ok()?),
+        [Register(register), Memory(memory), Immediate(value)] => Instruction::with3(code, register, memory.build(addressing), i32::try_from(value).ok()?),
+        _ => return None,
+    };
+
+    instruction.ok()
+}
+
+/// Encodes the instruction and decodes it back, rejecting encodings whose immediates or branch
+/// targets got truncated or sign extended into something else.
+fn encode(instruction: &Instruction, operands: &[Operand], ip: usize, bitness: u32) -> Option<Vec<u8>> {
+    let mut encoder = Encoder::new(bitness);
+    encoder.encode(instruction, ip as u64).ok()?;
+    let bytes = encoder.take_buffer();
+
+    let decoded = Decoder::with_ip(bitness, &bytes, ip as u64, DecoderOptions::NONE).decode();
+    let matches = operands.iter().enumerate().all(|(i, operand)| match operand {
+        Operand::Immediate(value) => match decoded.op_kind(i as u32) {
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => decoded.near_branch_target() == *value as u64,
+            kind => decoded.try_immediate(i as u32).ok()
+                .map_or(false, |immediate| immediate_matches(kind, immediate, *value)),
+        },
+        _ => true,
+    });
+
+    match !decoded.is_invalid() && matches {
+        true => Some(bytes),
+        false => None,
+    }
+}
+
+fn immediate_matches(kind: OpKind, immediate: u64, value: i64) -> bool {
+    let bits = match kind {
+        OpKind::Immediate8 | OpKind::Immediate8_2nd => 8,
+        OpKind::Immediate16 | OpKind::Immediate8to16 => 16,
+        OpKind::Immediate32 | OpKind::Immediate8to32 => 32,
+        _ => return immediate == value as u64,
+    };
+
+    let mask = (1u64 << bits) - 1;
+    let fits = value >= -(1i64 << (bits - 1)) && value <= mask as i64;
+
+    fits && (immediate & mask) == (value as u64 & mask)
+}
+
+fn split_operands(operands: &str) -> Vec<&str> {
+    if operands.is_empty() {
+        return Vec::new();
+    }
+
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in operands.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(operands[start..i].trim());
+                start = i + 1;
+            },
+            _ => {}
+        }
+    }
+
+    parts.push(operands[start..].trim());
+    parts
+}
+
+fn parse_operand(operand: &str) -> Option<Operand> {
+    let lowered = operand.to_lowercase();
+
+    if let Some(register) = REGISTERS.get(lowered.as_str()) {
+        return Some(Operand::Register(*register));
+    }
+
+    if !lowered.contains('[') {
+        return parse_number(&lowered).map(Operand::Immediate);
+    }
+
+    let (prefix, expression) = lowered.split_once('[')?;
+    let expression = expression.strip_suffix(']')?;
+
+    let size = match prefix.trim().trim_end_matches("ptr").trim() {
+        "" => None,
+        "byte" => Some(1),
+        "word" => Some(2),
+        "dword" => Some(4),
+        "fword" => Some(6),
+        "qword" => Some(8),
+        "tbyte" => Some(10),
+        "xmmword" | "oword" => Some(16),
+        "ymmword" => Some(32),
+        "zmmword" => Some(64),
+        _ => return None,
+    };
+
+    let mut memory = Memory { base: Register::None, index: Register::None, scale: 1, displacement: 0, size };
+    for term in split_terms(expression) {
+        let (negative, term) = match term.strip_prefix('-') {
+            Some(term) => (true, term.trim()),
+            None => (false, term.trim_start_matches('+').trim()),
+        };
+
+        if let Some((left, right)) = term.split_once('*') {
+            let (register, scale) = match REGISTERS.get(left.trim()) {
+                Some(register) => (*register, parse_number(right.trim())?),
+                None => (*REGISTERS.get(right.trim())?, parse_number(left.trim())?),
+            };
+
+            if negative || memory.index != Register::None {
+                return None;
+            }
+
+            memory.index = register;
+            memory.scale = scale as u32;
+        } else if let Some(register) = REGISTERS.get(term) {
+            match (negative, memory.base, memory.index) {
+                (false, Register::None, _) => memory.base = *register,
+                (false, _, Register::None) => memory.index = *register,
+                _ => return None,
+            }
+        } else {
+            let value = parse_number(term)?;
+            memory.displacement = memory.displacement.wrapping_add(if negative { value.wrapping_neg() } else { value });
+        }
+    }
+
+    Some(Operand::Memory(memory))
+}
+
+fn split_terms(expression: &str) -> Vec<&str> {
+    let mut terms = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in expression.char_indices() {
+        if (c == '+' || c == '-') && i > start {
+            terms.push(expression[start..i].trim());
+            start = i;
+        }
+    }
+
+    terms.push(expression[start..].trim());
+    terms.into_iter().filter(|term| !term.is_empty()).collect()
+}
+
+/// Accepts `0x1F`, `1Fh` and decimal literals, optionally negative.
+pub fn parse_number(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(text) => (true, text.trim()),
+        None => (false, text),
+    };
+
+    let value = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()?
+    } else if let Some(hex) = text.strip_suffix('h').or_else(|| text.strip_suffix('H')) {
+        u64::from_str_radix(hex, 16).ok()?
+    } else {
+        text.parse::<u64>().ok()?
+    } as i64;
+
+    Some(if negative { value.wrapping_neg() } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disasm::decode;
+
+    const IP: usize = 0x140001000;
+
+    // assembles `source`, decodes the bytes again and returns the formatted instructions
+    fn round_trip(source: &str, bitness: u32) -> Vec<String> {
+        let bytes = assemble(source, IP, 0, bitness).unwrap();
+        let instructions = decode(&bytes, IP, usize::MAX, bitness);
+
+        assert_eq!(instructions.iter().map(|instruction| instruction.bytes.len()).sum::<usize>(), bytes.len());
+        instructions.iter()
+            .map(|instruction| format!("{} {}", instruction.mnemonic, instruction.operands).trim().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn assembles_every_operand_form() {
+        let cases = [
+            ("nop", "nop"),
+            ("push rbx", "push rbx"),
+            ("mov rax, rbx", "mov rax,rbx"),
+            ("mov eax, 1", "mov eax,1"),
+            ("mov rax, [rbx+8]", "mov rax,[rbx+8]"),
+            ("mov [rbx+rcx*4-10h], eax", "mov [rbx+rcx*4-10h],eax"),
+            ("mov dword ptr [rax], 1", "mov dword ptr [rax],1"),
+            ("imul eax, ecx, 10", "imul eax,ecx,0Ah"),
+            ("imul eax, [rbx], 10", "imul eax,[rbx],0Ah"),
+            ("jmp 0x140001100", "jmp 0000000140001100h"),
+            ("call 0x140002000", "call 0000000140002000h"),
+        ];
+
+        for (source, expected) in cases {
+            assert_eq!(round_trip(source, 64), vec![expected], "{}", source);
+        }
+    }
+
+    #[test]
+    fn encodes_absolute_and_rip_relative_operands() {
+        assert_eq!(round_trip("mov rax, [0x140001100]", 64), vec!["mov rax,[140001100h]"]);
+        assert_eq!(round_trip("mov rax, [rip+10h]", 64), vec!["mov rax,[140001017h]"]);
+    }
+
+    #[test]
+    fn ignores_the_size_of_address_only_operands() {
+        assert_eq!(round_trip("lea rax, qword ptr [rbx+rdx*2]", 64), vec!["lea rax,[rbx+rdx*2]"]);
+        assert_eq!(round_trip("lea rcx, [rbx+8]", 64), vec!["lea rcx,[rbx+8]"]);
+    }
+
+    #[test]
+    fn assembles_32_bit_code() {
+        assert_eq!(round_trip("mov eax, [ebx+4]", 32), vec!["mov eax,[ebx+4]"]);
+        assert_eq!(round_trip("push ebp", 32), vec!["push ebp"]);
+    }
+
+    #[test]
+    fn semicolons_separate_statements() {
+        assert_eq!(assemble("mov eax,1; ret", IP, 0, 64).unwrap(), vec![0xB8, 0x01, 0x00, 0x00, 0x00, 0xC3]);
+        assert_eq!(round_trip("mov eax, 1 // one\n// nothing here\nret", 64), vec!["mov eax,1", "ret"]);
+    }
+
+    #[test]
+    fn rejects_ambiguous_and_oversized_code() {
+        assert!(matches!(assemble("mov [rax], 1", IP, 0, 64), Err(MemoryError::AmbiguousOperandSize)));
+        assert!(matches!(assemble("mov rax, rbx", IP, 2, 64), Err(MemoryError::AssembledCodeTooLong)));
+        assert_eq!(assemble("ret", IP, 3, 64).unwrap(), vec![0xC3, 0x90, 0x90]);
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+
+use log::warn;
+use roxmltree::{Document, Node};
+
+use crate::{
+    error::MemoryError,
+    table::{AddressSpec, Entry, FreezeValue, Table, ValueType, MAX_VALUE_LENGTH},
+};
+
+// `None` for names defined by a script that can't be resolved statically, e.g. `alloc(newmem, ..)`,
+// so addresses using them are skipped instead of being read as hex
+type Symbols = HashMap<String, Option<AddressSpec>>;
+
+/// Converts a Cheat Engine `.CT` file into a `Table`. Groups are flattened, scripts and entries
+/// whose address can't be expressed as an `AddressSpec` (e.g. `[..]` or symbols allocated by a
+/// script) are skipped. `pointer_size` is 4 or 8, 0 picks the native size.
+///
+/// `aobscan` / `aobscanmodule` symbols of the auto assembler scripts and the user defined symbols
+/// are resolved into signatures and modules, frozen entries keep their last value as `freeze`.
+pub fn import(source: &str, pointer_size: usize) -> Result<Table, MemoryError> {
+    let document = Document::parse(source)
+        .map_err(|_| MemoryError::InvalidTable)?;
+
+    let root = document.root_element();
+    if !root.has_tag_name("CheatTable") {
+        return Err(MemoryError::InvalidTable);
+    }
+
+    let mut table = Table::default();
+    match pointer_size {
+        0 => {},
+        4 | 8 => table.pointer_size = pointer_size,
+        _ => return Err(MemoryError::InvalidTable),
+    }
+
+    let mut symbols = HashMap::new();
+    for script in root.descendants().filter(|node| node.has_tag_name("AssemblerScript")) {
+        collect_script_symbols(script.text().unwrap_or_default(), &mut symbols);
+    }
+
+    let defined = root.children()
+        .filter(|node| node.has_tag_name("UserdefinedSymbols"))
+        .flat_map(|node| node.children())
+        .filter(|node| node.has_tag_name("SymbolEntry"));
+
+    for symbol in defined {
+        let (Some(name), Some(address)) = (child_text(symbol, "Name"), child_text(symbol, "Address")) else {
+            continue;
+        };
+
+        let spec = parse_address(address, &symbols, None);
+        if spec.is_none() {
+            warn!("skipping the symbol {:?}, unsupported address {:?}", name, address);
+        }
+
+        symbols.insert(name.trim().to_lowercase(), spec);
+    }
+
+    for entries in root.children().filter(|node| node.has_tag_name("CheatEntries")) {
+        collect_entries(entries, None, &symbols, &mut table.entries);
+    }
+
+    Ok(table)
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).and_then(|child| child.text())
+}
+
+fn child_flag(node: Node, name: &str) -> bool {
+    child_text(node, name).is_some_and(|text| text.trim() == "1")
+}
+
+fn collect_entries(entries: Node, parent: Option<&AddressSpec>, symbols: &Symbols, output: &mut Vec<Entry>) {
+    for node in entries.children().filter(|node| node.has_tag_name("CheatEntry")) {
+        let description = child_text(node, "Description").unwrap_or_default().trim();
+        let description = description.strip_prefix('"')
+            .and_then(|description| description.strip_suffix('"'))
+            .unwrap_or(description);
+
+        let address = child_text(node, "Address")
+            .map(|address| (address, parse_address(address, symbols, parent).and_then(|spec| with_offsets(node, spec))));
+
+        if let Some(value_type) = value_type(node) {
+            match address.as_ref() {
+                Some((_, Some(spec))) => output.push(Entry {
+                    description: description.to_owned(),
+                    address: spec.clone(),
+                    value_type,
+                    length: value_length(node, value_type),
+                    freeze: freeze_value(node, value_type),
+                }),
+                Some((address, None)) => warn!("skipping {:?}, unsupported address {:?}", description, address),
+                None => warn!("skipping {:?}, it has no address", description),
+            }
+        }
+
+        // children of a group with an address may be relative to it, e.g. `+10`
+        if let Some(children) = child(node, "CheatEntries") {
+            let spec = address.and_then(|(_, spec)| spec);
+            collect_entries(children, spec.as_ref().or(parent), symbols, output);
+        }
+    }
+}
+
+/// Appends the `<Offsets>` of the entry, listed from the last applied to the first.
+fn with_offsets(node: Node, mut spec: AddressSpec) -> Option<AddressSpec> {
+    let Some(offsets) = child(node, "Offsets") else {
+        return Some(spec);
+    };
+
+    let offsets = offsets.children()
+        .filter(|offset| offset.has_tag_name("Offset"))
+        .map(|offset| parse_hex(offset.text().unwrap_or_default()))
+        .collect::<Option<Vec<_>>>()?;
+
+    spec.offsets.extend(offsets.into_iter().rev());
+    Some(spec)
+}
+
+fn value_type(node: Node) -> Option<ValueType> {
+    let signed = child_flag(node, "ShowAsSigned");
+
+    Some(match child_text(node, "VariableType")?.trim() {
+        "Byte" if signed => ValueType::I8,
+        "Byte" => ValueType::U8,
+        "2 Bytes" if signed => ValueType::I16,
+        "2 Bytes" => ValueType::U16,
+        "4 Bytes" if signed => ValueType::I32,
+        "4 Bytes" => ValueType::U32,
+        "8 Bytes" if signed => ValueType::I64,
+        "8 Bytes" => ValueType::U64,
+        "Float" => ValueType::F32,
+        "Double" => ValueType::F64,
+        "String" if child_flag(node, "Unicode") => ValueType::WString,
+        "String" => ValueType::CString,
+        "Array of byte" => ValueType::Bytes,
+        // auto assembler scripts, binary and custom types
+        _ => return None,
+    })
+}
+
+fn value_length(node: Node, value_type: ValueType) -> usize {
+    let length = match value_type {
+        ValueType::CString | ValueType::WString => child_text(node, "Length"),
+        ValueType::Bytes => child_text(node, "ByteLength"),
+        _ => return 0,
+    };
+
+    // cheat engine's default string length
+    length.and_then(|length| length.trim().parse::<usize>().ok())
+        .unwrap_or(10)
+        .min(MAX_VALUE_LENGTH)
+}
+
+fn freeze_value(node: Node, value_type: ValueType) -> Option<FreezeValue> {
+    let state = child(node, "LastState")?;
+    if state.attribute("Activated") != Some("1") {
+        return None;
+    }
+
+    let value = state.attribute("Value")?.trim();
+    match value_type {
+        ValueType::F32 | ValueType::F64 => value.parse().ok().map(FreezeValue::Float),
+        ValueType::CString | ValueType::WString | ValueType::Bytes => Some(FreezeValue::Text(value.to_owned())),
+        _ if child_flag(node, "ShowAsHex") => parse_hex(value).map(FreezeValue::Integer),
+        _ => value.parse::<i64>().ok()
+            .or_else(|| value.parse::<u64>().ok().map(|value| value as i64))
+            .map(FreezeValue::Integer),
+    }
+}
+
+/// Cheat engine numbers are hex, with or without a prefix.
+fn parse_hex(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+
+    let digits = digits.strip_prefix("0x")
+        .or_else(|| digits.strip_prefix('$'))
+        .unwrap_or(digits);
+
+    let value = u64::from_str_radix(digits, 16).ok()? as i64;
+    Some(if negative { value.wrapping_neg() } else { value })
+}
+
+/// Parses sums like `"game.exe"+1F00`, `INJECT+3-8` or `+10` (relative to the parent). Brackets
+/// and multiplication are not supported.
+fn parse_address(text: &str, symbols: &Symbols, parent: Option<&AddressSpec>) -> Option<AddressSpec> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(['[', ']', '*', '(', ')']) {
+        return None;
+    }
+
+    let relative = text.starts_with(['+', '-']);
+    let mut spec = match relative {
+        true => parent?.clone(),
+        false => AddressSpec::default(),
+    };
+
+    let mut delta = 0i64;
+    let mut terms = Vec::new();
+    let mut start = 0;
+    for (index, char) in text.char_indices().filter(|(_, char)| ['+', '-'].contains(char)) {
+        terms.push(&text[start..index]);
+        start = index;
+    }
+    terms.push(&text[start..]);
+
+    for term in terms.into_iter().filter(|term| !term.is_empty()) {
+        let (negative, term) = match term.split_at(term.find(|char: char| char != '+' && char != '-').unwrap_or(term.len())) {
+            (sign, term) if sign.len() <= 1 => (sign == "-", term.trim()),
+            _ => return None,
+        };
+
+        let term = term.strip_prefix('"')
+            .and_then(|term| term.strip_suffix('"'))
+            .unwrap_or(term);
+
+        let base = match symbols.get(&term.to_lowercase()) {
+            Some(symbol) => Some(symbol.clone()?),
+            None => term.contains('.').then(|| AddressSpec { module: Some(term.to_owned()), ..Default::default() }),
+        };
+
+        match base {
+            // a module or symbol has to be the only base of the address
+            Some(base) => {
+                if negative || relative || spec.module.is_some() || spec.signature.is_some() {
+                    return None;
+                }
+
+                spec.module = base.module;
+                spec.signature = base.signature;
+                delta = delta.wrapping_add(base.offset);
+            },
+            None => {
+                let value = parse_hex(term)?;
+                delta = delta.wrapping_add(if negative { value.wrapping_neg() } else { value });
+            }
+        }
+    }
+
+    match spec.offsets.last_mut() {
+        Some(last) => *last = last.wrapping_add(delta),
+        None => spec.offset = spec.offset.wrapping_add(delta),
+    }
+
+    Some(spec)
+}
+
+/// Collects `aobscan(name, bytes)` and `aobscanmodule(name, module, bytes)` definitions, and the
+/// names of allocations and labels.
+fn collect_script_symbols(script: &str, symbols: &mut Symbols) {
+    for line in script.lines() {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let Some((command, arguments)) = line.split_once('(') else {
+            continue;
+        };
+
+        let arguments = arguments.trim_end().trim_end_matches(')')
+            .split(',')
+            .map(str::trim)
+            .collect::<Vec<_>>();
+
+        let (name, spec) = match (command.trim().to_lowercase().as_str(), arguments.as_slice()) {
+            ("aobscan", [name, bytes]) => (name, signature_symbol(name, None, bytes)),
+            ("aobscanmodule", [name, module, bytes]) => (name, signature_symbol(name, Some(module), bytes)),
+            ("aobscanregion" | "alloc" | "globalalloc" | "label", [name, ..]) => (name, None),
+            _ => continue,
+        };
+
+        // [DISABLE] sections don't redefine symbols, the first definition wins
+        symbols.entry(name.to_lowercase()).or_insert(spec);
+    }
+}
+
+fn signature_symbol(name: &str, module: Option<&str>, bytes: &str) -> Option<AddressSpec> {
+    let Some(signature) = normalize_signature(bytes) else {
+        warn!("skipping the symbol {:?}, unsupported signature {:?}", name, bytes);
+        return None;
+    };
+
+    Some(AddressSpec {
+        module: module.map(|module| module.trim_matches('"').to_owned()),
+        signature: Some(signature),
+        ..Default::default()
+    })
+}
+
+/// `488B05????????` and `48 8B 05 ** ** ** **` both become `48 8B 05 ?? ?? ?? ??`, nibble
+/// wildcards are not supported.
+fn normalize_signature(bytes: &str) -> Option<String> {
+    let compact = bytes.split_whitespace()
+        .map(|token| match token {
+            "?" | "*" => "??",
+            _ => token,
+        })
+        .collect::<String>();
+
+    if compact.is_empty() || compact.len() % 2 != 0 {
+        return None;
+    }
+
+    compact.as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [b'?' | b'*', b'?' | b'*'] => Some("??".to_owned()),
+            [high, low] if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() => Some(format!("{}{}", *high as char, *low as char).to_uppercase()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|tokens| tokens.join(" "))
+}
@@ -1,10 +1,11 @@
 use core::panic;
 // Alias types
 // -------------------------------------------------------
-use std::{collections::HashMap, ffi::{c_char, CStr}, marker::PhantomData, ops::{Deref, DerefMut, FromResidual}, sync::Arc};
+use std::{collections::{BTreeMap, HashMap}, ffi::{c_char, CStr}, marker::PhantomData, ops::{Deref, DerefMut, FromResidual}, sync::{atomic::{AtomicBool, Ordering}, Arc}};
 
+use log::warn;
 use rust_i18n::{error::ErrorDetails, Backend};
-use udbg::memory::MemoryPage;
+use udbg::{memory::{MemoryPage, ReadMemoryUtils}, pe::*, target::{TargetUtil, UDbgTarget}};
 
 use crate::helpers::like::CStringLike;
 
@@ -12,9 +13,11 @@ pub type ArcM<T> = Arc<parking_lot::Mutex<T>>;
 
 pub type IEngine = dyn udbg::target::UDbgEngine;
 pub type ITarget = dyn udbg::target::UDbgTarget;
+pub type IMemoryTarget = dyn MemoryTarget;
 
 pub type TargetHandle = usize;
-pub type Targets = Vec<Arc<ITarget>>;
+/// Indexed by `TargetHandle`, detached targets leave a `None` behind so handles stay stable.
+pub type Targets = Vec<Option<Arc<IMemoryTarget>>>;
 
 pub type EngineHandleArc = ArcM<IEngineHandle>;
 pub type TargetsArcM = ArcM<Targets>;
@@ -215,7 +218,9 @@ pub struct ByteBuffer {
     ptr: *mut u8,
     count: usize,
     capacity: usize,
-    size: usize
+    size: usize,
+    /// alignment of the elements, `free` needs it to rebuild the allocation layout
+    align: usize,
 }
 
 impl ByteBuffer {
@@ -242,26 +247,21 @@ impl ByteBuffer {
     }
 
     pub unsafe fn from_vec(bytes: Vec<u8>) -> Self {
-        let mut v = std::mem::ManuallyDrop::new(bytes);
-        let elem_size = std::mem::size_of::<u8>();
-
-        Self {
-            ptr: v.as_mut_ptr(),
-            count: v.len(),
-            capacity: v.capacity(),
-            size: elem_size * v.len(),
-        }
+        Self::from_sized_vec(bytes)
     }
 
+    /// Takes over the elements, the allocation is shrunk to fit so it is described by `size` and
+    /// `align` alone (an empty buffer owns no allocation).
     pub unsafe fn from_sized_vec<T: Sized>(vec: Vec<T>) -> Self {
-        let mut v = std::mem::ManuallyDrop::new(vec);
-        let bytes = std::mem::size_of::<T>();
+        let count = vec.len();
+        let ptr = Box::into_raw(vec.into_boxed_slice()) as *mut T;
 
         Self {
-            ptr: v.as_mut_ptr() as *mut u8,
-            count: v.len(),
-            capacity: v.capacity(),
-            size: bytes * v.len()
+            ptr: ptr as *mut u8,
+            count,
+            capacity: count,
+            size: std::mem::size_of::<T>() * count,
+            align: std::mem::align_of::<T>(),
         }
     }
 
@@ -312,6 +312,46 @@ impl ByteBuffer {
     pub unsafe fn into_raw(self) -> *mut ByteBuffer {
         Box::into_raw(Box::new(self))
     }
+
+    /// Releases the elements of a buffer built by `from_vec` / `from_sized_vec`. The element type
+    /// is gone by then, the allocation is `size` bytes aligned to `align`.
+    pub unsafe fn free(self) {
+        // empty buffers and zero sized elements own no allocation
+        if self.ptr.is_null() || self.size == 0 {
+            return;
+        }
+
+        match std::alloc::Layout::from_size_align(self.size, self.align) {
+            Ok(layout) => std::alloc::dealloc(self.ptr, layout),
+            Err(_) => warn!("refusing to free a buffer with an invalid layout, {:?} bytes aligned to {:?}", self.size, self.align),
+        }
+    }
+}
+
+// Page filter, committed pages that are readable, writable or executable as flagged, and
+// only `MEM_MAPPED` ones when `mapped` is set
+// ----------------------------------------------------
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PageFilter {
+    pub mapped: bool,
+    pub readable: bool,
+    pub writable: bool,
+    pub executable: bool,
+}
+
+impl Default for PageFilter {
+    fn default() -> Self {
+        Self { mapped: false, readable: true, writable: true, executable: true }
+    }
+}
+
+// Forced write result
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CWriteResult {
+    pub written: usize,
+    pub mechanism: WriteMechanism,
 }
 
 // Memory page
@@ -344,4 +384,439 @@ impl From<&MemoryPage> for CMemoryPageInfo {
             alloc_base: value.alloc_base
         }
     }
+}
+
+// Pluggable memory target
+// ----------------------------------------------------
+pub trait MemoryTarget: Send + Sync {
+    fn pid(&self) -> u32;
+
+    fn collect_memory_info(&self) -> Vec<MemoryPage>;
+
+    /// Reads as many contiguous bytes as possible starting at `address`.
+    /// Returns `None` when not even the first byte is readable.
+    fn read_memory<'a>(&self, address: usize, buffer: &'a mut [u8]) -> Option<&'a mut [u8]>;
+
+    /// Returns the number of bytes written, `None` when the write failed.
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Option<usize>;
+
+    fn detach(&self);
+
+    /// Allocates `size` bytes with `PAGE_*` protection, preferably at or close to `near_address`.
+    fn allocate_memory(&self, size: usize, protection: u32, near_address: usize) -> Option<usize> {
+        None
+    }
+
+    fn free_memory(&self, address: usize, size: usize) -> bool {
+        false
+    }
+
+    /// Changes the protection of the pages covering the range. Returns the previous protection of
+    /// the first page, like `VirtualProtectEx`, callers restoring a range with mixed protections
+    /// have to go page by page (see `write_reprotected`).
+    fn protect_memory(&self, address: usize, size: usize, protection: u32) -> Option<u32> {
+        None
+    }
+
+    /// Writes regardless of the page protection and reports how the bytes got there.
+    fn write_memory_forced(&self, address: usize, buffer: &[u8]) -> Option<(usize, WriteMechanism)> {
+        match self.write_memory(address, buffer) {
+            Some(written) => Some((written, WriteMechanism::Direct)),
+            None => write_reprotected(self, address, buffer),
+        }
+    }
+
+    fn read_bytes(&self, address: usize, size: usize) -> Vec<u8> {
+        let mut buffer = vec![0u8; size];
+        let len = match self.read_memory(address, &mut buffer) {
+            Some(slice) => slice.len(),
+            None => 0,
+        };
+        buffer.truncate(len);
+
+        buffer
+    }
+}
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMechanism {
+    Direct = 1,
+    ProcMem = 2,
+    Reprotect = 3,
+}
+
+/// Makes the pages covering the range writable, writes and puts the original protections back.
+pub fn write_reprotected<T: MemoryTarget + ?Sized>(target: &T, address: usize, buffer: &[u8]) -> Option<(usize, WriteMechanism)> {
+    let end = address + buffer.len();
+    let pages = target.collect_memory_info()
+        .into_iter()
+        .filter(|page| page.base < end && address < page.base + page.size)
+        .collect::<Vec<_>>();
+
+    let mut changed = Vec::with_capacity(pages.len());
+    for page in pages.iter() {
+        let start = page.base.max(address);
+        let size = (page.base + page.size).min(end) - start;
+
+        match target.protect_memory(start, size, PAGE_EXECUTE_READWRITE) {
+            Some(old) => changed.push((start, size, old)),
+            None => break,
+        }
+    }
+
+    let written = match changed.len() == pages.len() && !pages.is_empty() {
+        true => target.write_memory(address, buffer),
+        false => None,
+    };
+
+    for (start, size, old) in changed {
+        _ = target.protect_memory(start, size, old);
+    }
+
+    written.map(|written| (written, WriteMechanism::Reprotect))
+}
+
+// udbg backed target
+pub struct ITargetHandle {
+    inner: Arc<ITarget>,
+}
+
+impl ITargetHandle {
+    pub fn new(inner: Arc<ITarget>) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &Arc<ITarget> {
+        &self.inner
+    }
+}
+
+impl MemoryTarget for ITargetHandle {
+    fn pid(&self) -> u32 {
+        self.inner.pid()
+    }
+
+    fn collect_memory_info(&self) -> Vec<MemoryPage> {
+        self.inner.collect_memory_info()
+    }
+
+    fn read_memory<'a>(&self, address: usize, buffer: &'a mut [u8]) -> Option<&'a mut [u8]> {
+        self.inner.read_memory(address, buffer)
+    }
+
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Option<usize> {
+        self.inner.write_memory(address, buffer)
+    }
+
+    fn detach(&self) {
+        _ = self.inner.detach();
+    }
+
+    #[cfg(target_os = "linux")]
+    fn write_memory_forced(&self, address: usize, buffer: &[u8]) -> Option<(usize, WriteMechanism)> {
+        use std::os::unix::fs::FileExt;
+
+        if let Some(written) = self.write_memory(address, buffer) {
+            return Some((written, WriteMechanism::Direct));
+        }
+
+        // the kernel ignores page protection for writes through /proc/<pid>/mem, a short write
+        // (e.g. an unmapped page in the range) falls back to reprotecting
+        let written = std::fs::OpenOptions::new()
+            .write(true)
+            .open(format!("/proc/{}/mem", self.pid()))
+            .and_then(|file| file.write_all_at(buffer, address as u64));
+
+        match written {
+            Ok(_) => Some((buffer.len(), WriteMechanism::ProcMem)),
+            Err(_) => write_reprotected(self, address, buffer),
+        }
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn allocate_memory(&self, size: usize, protection: u32, near_address: usize) -> Option<usize> {
+        crate::remote::mmap(self.pid(), size, protection, near_address)
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn free_memory(&self, address: usize, size: usize) -> bool {
+        crate::remote::munmap(self.pid(), address, size)
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    fn protect_memory(&self, address: usize, size: usize, protection: u32) -> Option<u32> {
+        let old = self.collect_memory_info()
+            .into_iter()
+            .find(|page| page.base <= address && address < page.base + page.size)?
+            .protect;
+
+        crate::remote::mprotect(self.pid(), address, size, protection)
+            .then_some(old)
+    }
+
+    fn read_bytes(&self, address: usize, size: usize) -> Vec<u8> {
+        self.inner.read_bytes(address, size)
+    }
+}
+
+// In-process mock target, backed by an in-memory page map
+pub struct MockPage {
+    pub page: MemoryPage,
+    pub data: Vec<u8>,
+    pub readable: bool,
+    pub fail_writes: bool,
+}
+
+impl MockPage {
+    pub fn new(base: usize, data: Vec<u8>, protect: u32) -> Self {
+        let page = MemoryPage {
+            base,
+            alloc_base: base,
+            size: data.len(),
+            state: MEM_COMMIT,
+            type_: MEM_PRIVATE,
+            protect,
+            ..Default::default()
+        };
+
+        Self { page, data, readable: true, fail_writes: false }
+    }
+
+    fn end(&self) -> usize {
+        self.page.base + self.data.len()
+    }
+
+    /// The part of the page within `start..end`, keeping its flags.
+    fn slice(&self, start: usize, end: usize, protect: u32) -> MockPage {
+        let mut page = MockPage::new(start, self.data[start - self.page.base..end - self.page.base].to_vec(), protect);
+        page.page.alloc_base = self.page.alloc_base;
+        page.readable = self.readable;
+        page.fail_writes = self.fail_writes;
+        page
+    }
+
+    fn is_writable(&self) -> bool {
+        (self.page.protect & (PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY)) > 0
+    }
+}
+
+pub struct MockTarget {
+    pid: u32,
+    pages: parking_lot::Mutex<BTreeMap<usize, MockPage>>,
+    detached: AtomicBool,
+}
+
+impl MockTarget {
+    pub fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            pages: parking_lot::Mutex::new(BTreeMap::new()),
+            detached: AtomicBool::new(false),
+        }
+    }
+
+    pub fn map(&self, base: usize, data: Vec<u8>, protect: u32) -> &Self {
+        self.map_page(MockPage::new(base, data, protect))
+    }
+
+    pub fn map_page(&self, page: MockPage) -> &Self {
+        self.pages.lock().insert(page.page.base, page);
+        self
+    }
+
+    /// Maps a committed page whose contents can not be read, like a guard region.
+    pub fn map_hole(&self, base: usize, size: usize, protect: u32) -> &Self {
+        let mut page = MockPage::new(base, vec![0u8; size], protect);
+        page.readable = false;
+        self.map_page(page)
+    }
+
+    pub fn unmap(&self, base: usize) -> Option<MockPage> {
+        self.pages.lock().remove(&base)
+    }
+
+    pub fn set_protection(&self, base: usize, protect: u32) -> Option<u32> {
+        self.pages.lock().get_mut(&base)
+            .map(|page| std::mem::replace(&mut page.page.protect, protect))
+    }
+
+    pub fn set_fail_writes(&self, base: usize, fail: bool) {
+        if let Some(page) = self.pages.lock().get_mut(&base) {
+            page.fail_writes = fail;
+        }
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.detached.load(Ordering::Relaxed)
+    }
+
+    pub fn with_page<R>(&self, base: usize, f: impl FnOnce(&mut MockPage) -> R) -> Option<R> {
+        self.pages.lock().get_mut(&base).map(f)
+    }
+}
+
+impl MemoryTarget for MockTarget {
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    fn collect_memory_info(&self) -> Vec<MemoryPage> {
+        self.pages.lock().values()
+            .map(|page| page.page.clone())
+            .collect()
+    }
+
+    fn read_memory<'a>(&self, address: usize, buffer: &'a mut [u8]) -> Option<&'a mut [u8]> {
+        let pages = self.pages.lock();
+        let mut read = 0;
+
+        while read < buffer.len() {
+            let cursor = address + read;
+            let page = match pages.range(..=cursor).next_back() {
+                Some((_, page)) if page.readable && cursor < page.end() => page,
+                _ => break,
+            };
+
+            let offset = cursor - page.page.base;
+            let count = (page.data.len() - offset).min(buffer.len() - read);
+            buffer[read..read + count].copy_from_slice(&page.data[offset..offset + count]);
+            read += count;
+        }
+
+        match read {
+            0 => None,
+            _ => Some(&mut buffer[..read]),
+        }
+    }
+
+    fn write_memory(&self, address: usize, buffer: &[u8]) -> Option<usize> {
+        let mut pages = self.pages.lock();
+
+        // like WriteProcessMemory, a write spanning several pages either lands whole or not at all
+        let mut cursor = address;
+        let end = address.checked_add(buffer.len())?;
+        while cursor < end {
+            match pages.range(..=cursor).next_back() {
+                Some((_, page)) if cursor < page.end() && !page.fail_writes && page.is_writable() => cursor = page.end(),
+                _ => return None,
+            }
+        }
+
+        let mut written = 0;
+        while written < buffer.len() {
+            let cursor = address + written;
+            let (_, page) = pages.range_mut(..=cursor).next_back()?;
+            let offset = cursor - page.page.base;
+            let count = (page.data.len() - offset).min(buffer.len() - written);
+            page.data[offset..offset + count].copy_from_slice(&buffer[written..written + count]);
+            written += count;
+        }
+
+        Some(buffer.len())
+    }
+
+    fn detach(&self) {
+        self.detached.store(true, Ordering::Relaxed);
+    }
+
+    fn allocate_memory(&self, size: usize, protection: u32, near_address: usize) -> Option<usize> {
+        const GRANULARITY: usize = 0x1000;
+
+        if size == 0 {
+            return None;
+        }
+
+        let size = (size + GRANULARITY - 1) & !(GRANULARITY - 1);
+        let mut pages = self.pages.lock();
+        let mut base = match near_address {
+            0 => 0x10000,
+            address => address & !(GRANULARITY - 1),
+        };
+
+        // bump past every page overlapping the candidate range
+        while let Some(page) = pages.values().find(|page| page.page.base < base + size && base < page.end()) {
+            base = (page.end() + GRANULARITY - 1) & !(GRANULARITY - 1);
+        }
+
+        pages.insert(base, MockPage::new(base, vec![0u8; size], protection));
+        Some(base)
+    }
+
+    /// `size` 0 releases the whole allocation at `address`, otherwise every page overlapping the
+    /// range is unmapped or trimmed to the part outside of it, like `munmap`.
+    fn free_memory(&self, address: usize, size: usize) -> bool {
+        if size == 0 {
+            return self.unmap(address).is_some();
+        }
+
+        let end = address.saturating_add(size);
+        let mut pages = self.pages.lock();
+        let overlapping = pages.values()
+            .filter(|page| page.page.base < end && address < page.end())
+            .map(|page| page.page.base)
+            .collect::<Vec<_>>();
+
+        for base in overlapping.iter() {
+            let Some(page) = pages.remove(base) else {
+                continue;
+            };
+
+            if page.page.base < address {
+                pages.insert(page.page.base, page.slice(page.page.base, address, page.page.protect));
+            }
+
+            if end < page.end() {
+                pages.insert(end, page.slice(end, page.end(), page.page.protect));
+            }
+        }
+
+        !overlapping.is_empty()
+    }
+
+    /// Changes every page overlapping the range, splitting the ones it covers partially, like
+    /// `mprotect`. Fails without changing anything when part of the range is unmapped.
+    fn protect_memory(&self, address: usize, size: usize, protection: u32) -> Option<u32> {
+        let end = address.saturating_add(size);
+        let mut pages = self.pages.lock();
+
+        let old = match pages.range(..=address).next_back() {
+            Some((_, page)) if address < page.end() => page.page.protect,
+            _ => return None,
+        };
+
+        let mut cursor = address;
+        while cursor < end {
+            match pages.range(..=cursor).next_back() {
+                Some((_, page)) if cursor < page.end() => cursor = page.end(),
+                _ => return None,
+            }
+        }
+
+        let overlapping = pages.values()
+            .filter(|page| page.page.base < end && address < page.end())
+            .map(|page| page.page.base)
+            .collect::<Vec<_>>();
+
+        for base in overlapping {
+            let Some(page) = pages.remove(&base) else {
+                continue;
+            };
+
+            let start = page.page.base.max(address);
+            let stop = page.end().min(end);
+
+            if page.page.base < start {
+                pages.insert(page.page.base, page.slice(page.page.base, start, page.page.protect));
+            }
+
+            pages.insert(start, page.slice(start, stop, protection));
+
+            if stop < page.end() {
+                pages.insert(stop, page.slice(stop, page.end(), page.page.protect));
+            }
+        }
+
+        Some(old)
+    }
 }
\ No newline at end of file
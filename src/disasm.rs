@@ -0,0 +1,177 @@
+use std::ffi::c_char;
+
+use iced_x86::{Decoder, DecoderOptions, Formatter, IntelFormatter, OpKind};
+
+use crate::{definitions::IMemoryTarget, helpers::like::CStringLike};
+
+// longest encodable x86 instruction
+pub const MAX_INSTRUCTION_LENGTH: usize = 15;
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub address: usize,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    pub operands: String,
+    pub branch_target: Option<usize>,
+    pub inner: iced_x86::Instruction,
+}
+
+/// Falls back to the bitness of the library itself for anything but 16, 32 or 64.
+pub fn normalize_bitness(bitness: u32) -> u32 {
+    match bitness {
+        16 | 32 | 64 => bitness,
+        _ => usize::BITS,
+    }
+}
+
+// bytes read per step, so a huge `count` doesn't turn into a huge allocation up front
+const CHUNK_SIZE: usize = 0x1000;
+
+/// Decodes up to `count` instructions starting at `address`, stopping at the first unreadable
+/// or invalid one.
+pub fn disassemble(target: &IMemoryTarget, address: usize, count: usize, bitness: u32) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut cursor = address;
+
+    while instructions.len() < count {
+        let remaining = count - instructions.len();
+        let size = remaining.saturating_mul(MAX_INSTRUCTION_LENGTH).min(CHUNK_SIZE);
+        let buffer = target.read_bytes(cursor, size);
+
+        // an instruction starting close to the end of a full chunk may continue in the next one,
+        // it is decoded again from there
+        let more = size == CHUNK_SIZE && buffer.len() == size;
+        let limit = match more {
+            true => cursor.wrapping_add(CHUNK_SIZE - MAX_INSTRUCTION_LENGTH),
+            false => usize::MAX,
+        };
+
+        let decoded = decode(&buffer, cursor, remaining, bitness)
+            .into_iter()
+            .take_while(|instruction| instruction.address < limit)
+            .collect::<Vec<_>>();
+
+        let next = decoded.last().map(|last| last.address.wrapping_add(last.bytes.len()));
+        instructions.extend(decoded);
+
+        match next {
+            Some(next) if more => cursor = next,
+            _ => break,
+        }
+    }
+
+    instructions
+}
+
+pub fn decode(buffer: &[u8], address: usize, count: usize, bitness: u32) -> Vec<Instruction> {
+    let mut decoder = Decoder::with_ip(normalize_bitness(bitness), buffer, address as u64, DecoderOptions::NONE);
+    let mut formatter = IntelFormatter::new();
+    let mut instructions = Vec::new();
+
+    while decoder.can_decode() && instructions.len() < count {
+        let instruction = decoder.decode();
+        if instruction.is_invalid() {
+            break;
+        }
+
+        let mut mnemonic = String::new();
+        let mut operands = String::new();
+        formatter.format_mnemonic(&instruction, &mut mnemonic);
+        formatter.format_all_operands(&instruction, &mut operands);
+
+        let offset = instruction.ip() as usize - address;
+        instructions.push(Instruction {
+            address: instruction.ip() as usize,
+            bytes: buffer[offset..offset + instruction.len()].to_vec(),
+            mnemonic,
+            operands,
+            branch_target: branch_target(&instruction),
+            inner: instruction,
+        });
+    }
+
+    instructions
+}
+
+fn branch_target(instruction: &iced_x86::Instruction) -> Option<usize> {
+    match instruction.op0_kind() {
+        OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => Some(instruction.near_branch_target() as usize),
+        _ => None,
+    }
+}
+
+// Disassembled instruction
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CInstruction {
+    pub address: usize,
+    pub length: usize,
+    pub bytes: [u8; 16],
+    pub mnemonic: *const c_char,
+    pub operands: *const c_char,
+    /// 0 for instructions without a near branch operand
+    pub branch_target: usize,
+}
+
+impl From<&Instruction> for CInstruction {
+    fn from(value: &Instruction) -> Self {
+        let mut bytes = [0u8; 16];
+        bytes[..value.bytes.len()].copy_from_slice(&value.bytes);
+
+        CInstruction {
+            address: value.address,
+            length: value.bytes.len(),
+            bytes,
+            mnemonic: value.mnemonic.as_c_char_ptr(),
+            operands: value.operands.as_c_char_ptr(),
+            branch_target: value.branch_target.unwrap_or_default(),
+        }
+    }
+}
+
+/// Resolves the operand encoded at `offset..offset + len` of the code at `address` into the
+/// absolute address it refers to. Rip relative memory operands and near branches are decoded,
+/// other operands of a decoded instruction resolve to nothing. When no instruction decodes at
+/// `offset` the bytes are read as a displacement relative to the end of the operand.
+pub fn resolve_operand(target: &IMemoryTarget, address: usize, offset: usize, len: usize, bitness: u32) -> Option<usize> {
+    let code = target.read_bytes(address, offset + len + MAX_INSTRUCTION_LENGTH);
+    let mut decoder = Decoder::with_ip(normalize_bitness(bitness), &code, address as u64, DecoderOptions::NONE);
+
+    while decoder.can_decode() {
+        let position = decoder.position();
+        let instruction = decoder.decode();
+        if instruction.is_invalid() || position > offset {
+            break;
+        }
+
+        if offset >= position + instruction.len() {
+            continue;
+        }
+
+        let offsets = decoder.get_constant_offsets(&instruction);
+        let local = offset - position;
+
+        if instruction.is_ip_rel_memory_operand() && offsets.has_displacement() && offsets.displacement_offset() == local {
+            return Some(instruction.ip_rel_memory_address() as usize);
+        }
+
+        if branch_target(&instruction).is_some() && offsets.has_immediate() && offsets.immediate_offset() == local {
+            return branch_target(&instruction);
+        }
+
+        // the bytes are an immediate or an absolute displacement, there's nothing to resolve
+        return None;
+    }
+
+    let bytes = code.get(offset..offset + len)?;
+    let displacement = match len {
+        1 => bytes[0] as i8 as isize,
+        2 => i16::from_le_bytes(bytes.try_into().ok()?) as isize,
+        4 => i32::from_le_bytes(bytes.try_into().ok()?) as isize,
+        8 => i64::from_le_bytes(bytes.try_into().ok()?) as isize,
+        _ => return None,
+    };
+
+    Some((address + offset + len).wrapping_add_signed(displacement))
+}
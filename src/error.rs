@@ -11,6 +11,9 @@ pub enum MemoryError {
     #[error("process-already-attached")]
     ProcessAlreadyAttached,
 
+    #[error("failed-to-attach-process")]
+    FailedToAttachProcess,
+
     #[error("failed_to_write_process_memory")]
     FailedToWriteProcessMemory,
 
@@ -19,4 +22,88 @@ pub enum MemoryError {
 
     #[error("invalid-target-handle")]
     InvalidTargetHandle,
-}
\ No newline at end of file
+
+    #[error("failed-to-allocate-memory")]
+    FailedToAllocateMemory,
+
+    #[error("failed-to-free-memory")]
+    FailedToFreeMemory,
+
+    #[error("failed-to-protect-memory")]
+    FailedToProtectMemory,
+
+    #[error("patch-not-found")]
+    PatchNotFound,
+
+    #[error("patch-overlaps")]
+    PatchOverlaps,
+
+    #[error("patch-original-mismatch")]
+    PatchOriginalMismatch,
+
+    #[error("invalid-instruction")]
+    InvalidInstruction,
+
+    #[error("ambiguous-operand-size")]
+    AmbiguousOperandSize,
+
+    #[error("assembled-code-too-long")]
+    AssembledCodeTooLong,
+
+    #[error("signature-not-unique")]
+    SignatureNotUnique,
+
+    #[error("invalid-pattern")]
+    InvalidPattern,
+
+    #[error("pattern-missing-group")]
+    PatternMissingGroup,
+
+    #[error("capture-group-too-wide")]
+    CaptureGroupTooWide,
+
+    #[error("invalid-scan-parameters")]
+    InvalidScanParameters,
+
+    #[error("invalid-layout")]
+    InvalidLayout,
+
+    #[error("layout-not-found")]
+    LayoutNotFound,
+
+    #[error("abi-version-mismatch")]
+    AbiVersionMismatch,
+
+    #[error("abi-layout-mismatch")]
+    AbiLayoutMismatch,
+
+    #[error("failed-to-read-script")]
+    FailedToReadScript,
+
+    #[error("module-not-found")]
+    ModuleNotFound,
+
+    #[error("signature-not-found")]
+    SignatureNotFound,
+
+    #[error("invalid-table")]
+    InvalidTable,
+
+    #[error("table-not-found")]
+    TableNotFound,
+
+    #[error("invalid-expression")]
+    InvalidExpression,
+
+    #[error("symbol-not-found")]
+    SymbolNotFound,
+
+    #[error("invalid-symbol-name")]
+    InvalidSymbolName,
+
+    #[error("failed-to-read-symbols")]
+    FailedToReadSymbols,
+
+    #[error("failed-to-write-symbols")]
+    FailedToWriteSymbols,
+}
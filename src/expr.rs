@@ -0,0 +1,243 @@
+use crate::{
+    error::MemoryError,
+    process::Process,
+    symbol,
+    table::Resolver,
+};
+
+/// Parsed address expression, e.g. `[[game.so+1F00]+18]+40`.
+///
+/// Bare numbers are hex like in Cheat Engine (`1F00`, `0x1F00`), `#` marks decimals (`#16`).
+/// Names are host registered symbols, then module bases, `"quoted names"` are never numbers.
+/// `[..]` reads a pointer, `+ - *` and `( )` work as usual, arithmetic wraps.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(usize),
+    Name { name: String, quoted: bool },
+    Negate(Box<Expression>),
+    Add(Box<Expression>, Box<Expression>),
+    Subtract(Box<Expression>, Box<Expression>),
+    Multiply(Box<Expression>, Box<Expression>),
+    Dereference(Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(usize),
+    Name { name: String, quoted: bool },
+    Plus,
+    Minus,
+    Star,
+    Open(char),
+    Close(char),
+}
+
+// bounds `[` / `(` nesting so hostile input can't overflow the stack
+const MAX_NESTING: usize = 64;
+
+pub(crate) fn is_name_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || ['_', '.', '@', '$'].contains(&char)
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, MemoryError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((_, char)) = chars.next() {
+        let token = match char {
+            _ if char.is_whitespace() => continue,
+            '+' => Token::Plus,
+            '-' => Token::Minus,
+            '*' => Token::Star,
+            '[' | '(' => Token::Open(char),
+            ']' | ')' => Token::Close(char),
+            '"' => {
+                let mut name = String::new();
+                let mut closed = false;
+                for (_, char) in chars.by_ref() {
+                    if char == '"' {
+                        closed = true;
+                        break;
+                    }
+
+                    name.push(char);
+                }
+
+                if !closed || name.is_empty() {
+                    return Err(MemoryError::InvalidExpression);
+                }
+
+                Token::Name { name, quoted: true }
+            },
+            '#' => {
+                let mut digits = String::new();
+                while let Some((_, digit)) = chars.next_if(|(_, char)| char.is_ascii_digit()) {
+                    digits.push(digit);
+                }
+
+                Token::Number(digits.parse().map_err(|_| MemoryError::InvalidExpression)?)
+            },
+            _ if is_name_char(char) => {
+                let mut name = String::from(char);
+                while let Some((_, char)) = chars.next_if(|(_, char)| is_name_char(*char)) {
+                    name.push(char);
+                }
+
+                Token::Name { name, quoted: false }
+            },
+            _ => return Err(MemoryError::InvalidExpression),
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    nesting: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    // sum := product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Expression, MemoryError> {
+        let mut left = self.product()?;
+
+        loop {
+            left = match self.peek() {
+                Some(Token::Plus) => {
+                    self.next();
+                    Expression::Add(Box::new(left), Box::new(self.product()?))
+                },
+                Some(Token::Minus) => {
+                    self.next();
+                    Expression::Subtract(Box::new(left), Box::new(self.product()?))
+                },
+                _ => return Ok(left),
+            };
+        }
+    }
+
+    // product := unary ('*' unary)*
+    fn product(&mut self) -> Result<Expression, MemoryError> {
+        let mut left = self.unary()?;
+
+        while self.peek() == Some(&Token::Star) {
+            self.next();
+            left = Expression::Multiply(Box::new(left), Box::new(self.unary()?));
+        }
+
+        Ok(left)
+    }
+
+    // unary := '-' unary | '+' unary | number | name | '[' sum ']' | '(' sum ')'
+    fn unary(&mut self) -> Result<Expression, MemoryError> {
+        match self.next() {
+            Some(Token::Minus) => Ok(Expression::Negate(Box::new(self.nested(Self::unary)?))),
+            Some(Token::Plus) => self.nested(Self::unary),
+            Some(Token::Number(value)) => Ok(Expression::Number(value)),
+            Some(Token::Name { name, quoted }) => Ok(Expression::Name { name, quoted }),
+            Some(Token::Open(open)) => {
+                let inner = self.nested(Self::sum)?;
+                match (open, self.next()) {
+                    ('[', Some(Token::Close(']'))) => Ok(Expression::Dereference(Box::new(inner))),
+                    ('(', Some(Token::Close(')'))) => Ok(inner),
+                    _ => Err(MemoryError::InvalidExpression),
+                }
+            },
+            _ => Err(MemoryError::InvalidExpression),
+        }
+    }
+
+    fn nested(&mut self, rule: fn(&mut Self) -> Result<Expression, MemoryError>) -> Result<Expression, MemoryError> {
+        if self.nesting == MAX_NESTING {
+            return Err(MemoryError::InvalidExpression);
+        }
+
+        self.nesting += 1;
+        let expression = rule(self);
+        self.nesting -= 1;
+
+        expression
+    }
+}
+
+/// `0x1F00` and `1F00` are hex.
+pub(crate) fn parse_hex(name: &str) -> Option<usize> {
+    let digits = name.strip_prefix("0x")
+        .or_else(|| name.strip_prefix("0X"))
+        .unwrap_or(name);
+
+    usize::from_str_radix(digits, 16).ok()
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self, MemoryError> {
+        let mut parser = Parser { tokens: tokenize(source)?, position: 0, nesting: 0 };
+        let expression = parser.sum()?;
+
+        match parser.peek() {
+            None => Ok(expression),
+            Some(_) => Err(MemoryError::InvalidExpression),
+        }
+    }
+
+    /// Evaluates against the process, `[..]` reads `pointer_size` (4 or 8) bytes and the
+    /// addresses it reads from and the result wrap at that size.
+    pub fn evaluate(&self, process: &Process, pointer_size: usize) -> Result<usize, MemoryError> {
+        if ![4, 8].contains(&pointer_size) {
+            return Err(MemoryError::InvalidExpression);
+        }
+
+        let mut resolver = Resolver::new(process, pointer_size);
+        let address = self.evaluate_with(&mut resolver)?;
+        Ok(resolver.wrap(address))
+    }
+
+    pub(crate) fn evaluate_with(&self, resolver: &mut Resolver) -> Result<usize, MemoryError> {
+        Ok(match self {
+            Expression::Number(value) => *value,
+            Expression::Name { name, quoted } => {
+                if let Some(address) = symbol::lookup(resolver.process().target().as_ref(), name) {
+                    return Ok(address);
+                }
+
+                match (quoted, parse_hex(name)) {
+                    (false, Some(value)) => value,
+                    _ if name.contains('.') => resolver.module_base(name)?,
+                    _ => resolver.module_base(name).map_err(|_| MemoryError::SymbolNotFound)?,
+                }
+            },
+            Expression::Negate(inner) => inner.evaluate_with(resolver)?.wrapping_neg(),
+            Expression::Add(left, right) => left.evaluate_with(resolver)?.wrapping_add(right.evaluate_with(resolver)?),
+            Expression::Subtract(left, right) => left.evaluate_with(resolver)?.wrapping_sub(right.evaluate_with(resolver)?),
+            Expression::Multiply(left, right) => left.evaluate_with(resolver)?.wrapping_mul(right.evaluate_with(resolver)?),
+            Expression::Dereference(inner) => {
+                let address = inner.evaluate_with(resolver)?;
+                resolver.read_pointer(address)?
+            },
+        })
+    }
+}
+
+/// Parses and evaluates `source`, `pointer_size` 0 picks the native size.
+pub fn evaluate(process: &Process, source: &str, pointer_size: usize) -> Result<usize, MemoryError> {
+    let pointer_size = match pointer_size {
+        0 => std::mem::size_of::<usize>(),
+        size => size,
+    };
+
+    Expression::parse(source)?.evaluate(process, pointer_size)
+}
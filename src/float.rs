@@ -0,0 +1,162 @@
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use udbg::memory::MemoryPage;
+
+use crate::{definitions::IMemoryTarget, error::MemoryError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatType {
+    F32,
+    F64,
+}
+
+impl FloatType {
+    pub fn size(&self) -> usize {
+        match self {
+            FloatType::F32 => 4,
+            FloatType::F64 => 8,
+        }
+    }
+
+    fn read(&self, bytes: &[u8]) -> f64 {
+        match self {
+            FloatType::F32 => f32::from_le_bytes(bytes[..4].try_into().unwrap()) as f64,
+            FloatType::F64 => f64::from_le_bytes(bytes[..8].try_into().unwrap()),
+        }
+    }
+}
+
+/// Past this `10^decimals` scales every value beyond f64 precision, so all of them compare equal.
+const MAX_DECIMALS: u32 = 15;
+
+pub(crate) fn check_decimals(decimals: u32) -> Result<u32, MemoryError> {
+    match decimals <= MAX_DECIMALS {
+        true => Ok(decimals),
+        false => Err(MemoryError::InvalidScanParameters),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FloatMode {
+    /// the value shown is the memory value rounded to `decimals`
+    Rounded { decimals: u32 },
+    /// the value shown is the memory value cut after `decimals`
+    Truncated { decimals: u32 },
+    Epsilon { epsilon: f64 },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FloatMatcher {
+    pub value_type: FloatType,
+    pub mode: FloatMode,
+    pub value: f64,
+    pub alignment: usize,
+}
+
+impl FloatMatcher {
+    pub fn matches(&self, candidate: f64) -> bool {
+        if !candidate.is_finite() {
+            return false;
+        }
+
+        match self.mode {
+            FloatMode::Rounded { decimals } => {
+                let scale = 10f64.powi(decimals as i32);
+                (candidate * scale).round() == (self.value * scale).round()
+            },
+            FloatMode::Truncated { decimals } => {
+                let scale = 10f64.powi(decimals as i32);
+                (candidate * scale).trunc() == (self.value * scale).round()
+            },
+            FloatMode::Epsilon { epsilon } => (candidate - self.value).abs() <= epsilon,
+        }
+    }
+
+    /// Scans the pages, looking only at addresses that are multiples of the alignment.
+    pub fn scan(&self, target: &IMemoryTarget, pages: &[MemoryPage]) -> Vec<(usize, f64)> {
+        let size = self.value_type.size();
+
+        pages
+            .par_iter()
+            .flat_map_iter(|page| {
+                let buff = target.read_bytes(page.base, page.size);
+                let first = (page.base + self.alignment - 1) / self.alignment * self.alignment - page.base;
+
+                (first..buff.len().saturating_sub(size - 1))
+                    .step_by(self.alignment)
+                    .filter_map(|offset| {
+                        let value = self.value_type.read(&buff[offset..]);
+                        self.matches(value).then_some((page.base + offset, value))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Re-checks host supplied addresses, e.g. the result of an earlier scan.
+    pub fn scan_candidates(&self, target: &IMemoryTarget, candidates: &[usize]) -> Vec<(usize, f64)> {
+        let size = self.value_type.size();
+
+        candidates
+            .par_iter()
+            .filter(|address| *address % self.alignment == 0)
+            .filter_map(|address| {
+                let buff = target.read_bytes(*address, size);
+                if buff.len() != size {
+                    return None;
+                }
+
+                let value = self.value_type.read(&buff);
+                self.matches(value).then_some((*address, value))
+            })
+            .collect()
+    }
+}
+
+// Float query
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CFloatQuery {
+    /// 4 for f32, 8 for f64
+    pub value_type: u32,
+    /// 0 rounded to `decimals`, 1 truncated to `decimals`, 2 within `epsilon`
+    pub mode: u32,
+    pub value: f64,
+    /// at most 15
+    pub decimals: u32,
+    pub epsilon: f64,
+    /// 0 for the natural alignment of the type
+    pub alignment: usize,
+}
+
+impl TryFrom<&CFloatQuery> for FloatMatcher {
+    type Error = MemoryError;
+
+    fn try_from(value: &CFloatQuery) -> Result<Self, Self::Error> {
+        let value_type = match value.value_type {
+            4 => FloatType::F32,
+            8 => FloatType::F64,
+            _ => return Err(MemoryError::InvalidScanParameters),
+        };
+
+        let mode = match value.mode {
+            0 => FloatMode::Rounded { decimals: check_decimals(value.decimals)? },
+            1 => FloatMode::Truncated { decimals: check_decimals(value.decimals)? },
+            2 if value.epsilon >= 0.0 => FloatMode::Epsilon { epsilon: value.epsilon },
+            _ => return Err(MemoryError::InvalidScanParameters),
+        };
+
+        let alignment = match value.alignment {
+            0 => value_type.size(),
+            1 | 2 | 4 | 8 | 16 => value.alignment,
+            _ => return Err(MemoryError::InvalidScanParameters),
+        };
+
+        Ok(FloatMatcher { value_type, mode, value: value.value, alignment })
+    }
+}
+
+#[repr(C)]
+pub struct CFloatMatch {
+    pub address: usize,
+    pub value: f64,
+}
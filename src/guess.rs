@@ -0,0 +1,186 @@
+use std::ffi::c_char;
+
+use udbg::{memory::MemoryPage, pe::*};
+
+use crate::{definitions::IMemoryTarget, error::MemoryError, helpers::like::CStringLike};
+
+// anything below is more likely a small constant than an address
+const MIN_POINTER: u64 = 0x10000;
+const SMALL_INT_LIMIT: i64 = 0x100000;
+const MAX_STRING_PREVIEW: usize = 64;
+const MIN_STRING_LEN: usize = 4;
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotKind {
+    Unknown = 0,
+    Zero = 1,
+    ModulePointer = 2,
+    HeapPointer = 3,
+    StringPointer = 4,
+    Float = 5,
+    SmallInt = 6,
+}
+
+#[derive(Debug, Clone)]
+pub struct Slot {
+    pub offset: usize,
+    pub size: usize,
+    pub kind: SlotKind,
+    pub value: u64,
+    /// module name for module pointers, the text for string pointers
+    pub detail: Option<String>,
+}
+
+/// Classifies the `size` bytes at `address` slot by slot. Pointer sized, pointer aligned slots
+/// are checked against the page map first, everything else is looked at in 4 byte slots.
+/// `pointer_size` is the one of the target, 4 or 8, 0 picks the native size.
+pub fn guess_struct(target: &IMemoryTarget, address: usize, size: usize, pointer_size: usize) -> Result<Vec<Slot>, MemoryError> {
+    let pointer_size = match pointer_size {
+        0 => std::mem::size_of::<usize>(),
+        4 | 8 => pointer_size,
+        _ => return Err(MemoryError::InvalidScanParameters),
+    };
+
+    if size < 4 {
+        return Err(MemoryError::InvalidScanParameters);
+    }
+
+    let buffer = target.read_bytes(address, size);
+    if buffer.len() < 4 {
+        return Err(MemoryError::FailedtoReadProcessMemory);
+    }
+
+    let mut pages = target.collect_memory_info()
+        .into_iter()
+        .filter(|page| page.state == MEM_COMMIT && (page.protect & (PAGE_GUARD | PAGE_NOACCESS)) == 0)
+        .collect::<Vec<_>>();
+    pages.sort_by_key(|page| page.base);
+
+    let mut slots = Vec::new();
+    let mut offset = 0;
+
+    while offset + 4 <= buffer.len() {
+        if (address + offset) % pointer_size == 0 && offset + pointer_size <= buffer.len() {
+            let value = read_le(&buffer[offset..offset + pointer_size]);
+            // a double is only taken when its low half does not look like a value on its own,
+            // otherwise an int followed by a float would read as one
+            let is_double = pointer_size == 8
+                && is_plausible_float(f64::from_bits(value))
+                && matches!(classify_u32(value as u32), SlotKind::Zero | SlotKind::Unknown);
+
+            let slot = match value {
+                0 => Some((SlotKind::Zero, None)),
+                _ => classify_pointer(target, &pages, value)
+                    .or_else(|| is_double.then_some((SlotKind::Float, None))),
+            };
+
+            if let Some((kind, detail)) = slot {
+                slots.push(Slot { offset, size: pointer_size, kind, value, detail });
+                offset += pointer_size;
+                continue;
+            }
+        }
+
+        let value = read_le(&buffer[offset..offset + 4]);
+        slots.push(Slot { offset, size: 4, kind: classify_u32(value as u32), value, detail: None });
+        offset += 4;
+    }
+
+    Ok(slots)
+}
+
+fn classify_u32(value: u32) -> SlotKind {
+    match value {
+        0 => SlotKind::Zero,
+        raw if (raw as i32 as i64).abs() < SMALL_INT_LIMIT => SlotKind::SmallInt,
+        raw if is_plausible_float(f32::from_bits(raw) as f64) => SlotKind::Float,
+        _ => SlotKind::Unknown,
+    }
+}
+
+fn read_le(bytes: &[u8]) -> u64 {
+    let mut value = [0u8; 8];
+    value[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(value)
+}
+
+/// Values like 0.5, 100.0 or 3.14159; denormals, huge and tiny magnitudes are rejected.
+fn is_plausible_float(value: f64) -> bool {
+    value.is_normal() && (1e-4..=1e7).contains(&value.abs())
+}
+
+fn classify_pointer(target: &IMemoryTarget, pages: &[MemoryPage], value: u64) -> Option<(SlotKind, Option<String>)> {
+    if value < MIN_POINTER || value > usize::MAX as u64 {
+        return None;
+    }
+
+    let address = value as usize;
+    let index = pages.partition_point(|page| page.base + page.size <= address);
+    let page = pages.get(index).filter(|page| page.base <= address)?;
+
+    if let Some(text) = read_string(target, address) {
+        return Some((SlotKind::StringPointer, Some(text)));
+    }
+
+    match is_module(page) {
+        true => Some((SlotKind::ModulePointer, page.info.as_ref().map(|info| info.to_string()))),
+        false => Some((SlotKind::HeapPointer, None)),
+    }
+}
+
+fn is_module(page: &MemoryPage) -> bool {
+    page.type_ == MEM_IMAGE || page.info.as_ref().is_some_and(|info| info.contains('/') || info.contains('\\'))
+}
+
+/// Reads a nul terminated, printable ASCII or UTF-16LE string of at least `MIN_STRING_LEN`
+/// characters, truncated to `MAX_STRING_PREVIEW` characters.
+fn read_string(target: &IMemoryTarget, address: usize) -> Option<String> {
+    let bytes = target.read_bytes(address, MAX_STRING_PREVIEW * 2);
+    let is_printable = |c: u8| c.is_ascii_graphic() || c == b' ' || c == b'\t';
+
+    let ascii = bytes.iter()
+        .take(MAX_STRING_PREVIEW)
+        .take_while(|b| is_printable(**b))
+        .map(|b| *b as char)
+        .collect::<String>();
+
+    let terminated = |len: usize, unit: usize| len == MAX_STRING_PREVIEW
+        || bytes.get(len * unit..(len + 1) * unit).is_some_and(|nul| nul.iter().all(|b| *b == 0));
+
+    if ascii.len() >= MIN_STRING_LEN && terminated(ascii.len(), 1) {
+        return Some(ascii);
+    }
+
+    let wide = bytes.chunks_exact(2)
+        .take_while(|unit| unit[1] == 0 && is_printable(unit[0]))
+        .map(|unit| unit[0] as char)
+        .collect::<String>();
+
+    (wide.len() >= MIN_STRING_LEN && terminated(wide.len(), 2)).then_some(wide)
+}
+
+// Guessed slot
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CGuessedSlot {
+    pub offset: usize,
+    pub size: usize,
+    pub kind: SlotKind,
+    /// the raw little-endian slot value
+    pub value: u64,
+    /// null unless the slot is a module or string pointer
+    pub detail: *const c_char,
+}
+
+impl From<&Slot> for CGuessedSlot {
+    fn from(value: &Slot) -> Self {
+        CGuessedSlot {
+            offset: value.offset,
+            size: value.size,
+            kind: value.kind,
+            value: value.value,
+            detail: value.detail.as_ref().map_or(std::ptr::null(), |detail| detail.as_c_char_ptr()),
+        }
+    }
+}
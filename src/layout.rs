@@ -0,0 +1,310 @@
+use std::{collections::HashMap, sync::Arc};
+
+use lazy_static::lazy_static;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{definitions::{ArcM, IMemoryTarget}, error::MemoryError};
+
+lazy_static! {
+    // copied on write, so a read holds on to a snapshot instead of the lock
+    static ref LAYOUTS: Lazy<ArcM<Arc<HashMap<String, Layout>>>> = Lazy::new(|| ArcM::default());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    I8, U8, I16, U16, I32, U32, I64, U64,
+    F32, F64,
+    Bool,
+    /// pointer sized integer, followed into `target` when set
+    Pointer,
+    /// `target` laid out inline
+    Struct,
+    /// nul terminated, at most `length` bytes
+    CString,
+    /// nul terminated utf-16le, at most `length` code units
+    WString,
+    /// `length` raw bytes
+    Bytes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_number")]
+    pub offset: usize,
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub length: usize,
+    /// read as an array of `count` elements when set
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Layout {
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub size: usize,
+    pub fields: Vec<Field>,
+    /// `size`, or the end of the furthest field when it is 0, see `compute_sizes`
+    #[serde(skip)]
+    byte_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schema {
+    #[serde(default = "default_pointer_size", deserialize_with = "deserialize_number")]
+    pub pointer_size: usize,
+    pub layouts: HashMap<String, Layout>,
+}
+
+fn default_pointer_size() -> usize {
+    std::mem::size_of::<usize>()
+}
+
+/// Offsets and sizes are easier to read as hex, accept `"0x1C"` next to plain numbers.
+pub(crate) fn deserialize_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<usize, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Number {
+        Int(usize),
+        Text(String),
+    }
+
+    match Number::deserialize(deserializer)? {
+        Number::Int(value) => Ok(value),
+        Number::Text(text) => {
+            let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                Some(hex) => usize::from_str_radix(hex, 16),
+                None => text.parse::<usize>(),
+            };
+
+            parsed.map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+impl Field {
+    fn element_size(&self, layouts: &HashMap<String, Layout>) -> usize {
+        match self.field_type {
+            FieldType::I8 | FieldType::U8 | FieldType::Bool => 1,
+            FieldType::I16 | FieldType::U16 => 2,
+            FieldType::I32 | FieldType::U32 | FieldType::F32 => 4,
+            FieldType::I64 | FieldType::U64 | FieldType::F64 => 8,
+            FieldType::Pointer | FieldType::CString | FieldType::Bytes => self.length,
+            FieldType::WString => self.length * 2,
+            FieldType::Struct => self.target.as_ref()
+                .and_then(|target| layouts.get(target))
+                .map_or(0, |layout| layout.byte_size),
+        }
+    }
+}
+
+/// Computes `byte_size` of every layout once, failing when a layout inlines itself directly or
+/// through others.
+fn compute_sizes(layouts: &mut HashMap<String, Layout>) -> Result<(), MemoryError> {
+    let mut sizes = HashMap::new();
+    for id in layouts.keys() {
+        size_of(id, layouts, &mut sizes)?;
+    }
+
+    for (id, layout) in layouts.iter_mut() {
+        layout.byte_size = sizes[id].unwrap_or_default();
+    }
+
+    Ok(())
+}
+
+// `None` marks a layout whose size is being computed, reaching it again is an inline cycle
+fn size_of(id: &str, layouts: &HashMap<String, Layout>, sizes: &mut HashMap<String, Option<usize>>) -> Result<usize, MemoryError> {
+    match sizes.get(id) {
+        Some(Some(size)) => return Ok(*size),
+        Some(None) => return Err(MemoryError::InvalidLayout),
+        None => {},
+    }
+
+    // structs of unknown layouts are read as null
+    let Some(layout) = layouts.get(id) else {
+        return Ok(0);
+    };
+
+    sizes.insert(id.to_owned(), None);
+
+    let mut end = 0;
+    for field in layout.fields.iter() {
+        let element = match (field.field_type, field.target.as_deref()) {
+            (FieldType::Struct, Some(target)) => size_of(target, layouts, sizes)?,
+            _ => field.element_size(layouts),
+        };
+
+        end = end.max(field.offset.saturating_add(element.saturating_mul(field.count.max(1))));
+    }
+
+    let size = match layout.size {
+        0 => end,
+        size => size,
+    };
+
+    sizes.insert(id.to_owned(), Some(size));
+    Ok(size)
+}
+
+/// Parses a json (`format` 0) or toml (`format` 1) schema and registers its layouts, replacing
+/// layouts with the same id. Nothing is registered when a layout would inline itself directly or
+/// through others. Returns the number of registered layouts.
+pub fn load(source: &str, format: u32) -> Result<usize, MemoryError> {
+    let schema: Schema = match format {
+        0 => serde_json::from_str(source).map_err(|_| MemoryError::InvalidLayout)?,
+        1 => toml::from_str(source).map_err(|_| MemoryError::InvalidLayout)?,
+        _ => return Err(MemoryError::InvalidLayout),
+    };
+
+    if ![4, 8].contains(&schema.pointer_size) {
+        return Err(MemoryError::InvalidLayout);
+    }
+
+    let mut layouts = LAYOUTS.lock();
+    let mut updated = HashMap::clone(&layouts);
+    let count = schema.layouts.len();
+
+    for (id, mut layout) in schema.layouts {
+        // pointer size is per schema, keep it with the pointer fields
+        for field in layout.fields.iter_mut().filter(|field| field.field_type == FieldType::Pointer) {
+            match field.length {
+                0 => field.length = schema.pointer_size,
+                4 | 8 => {},
+                _ => return Err(MemoryError::InvalidLayout),
+            }
+        }
+
+        updated.insert(id, layout);
+    }
+
+    compute_sizes(&mut updated)?;
+    *layouts = Arc::new(updated);
+
+    Ok(count)
+}
+
+pub fn unload(id: &str) -> bool {
+    let mut layouts = LAYOUTS.lock();
+    let layouts = Arc::make_mut(&mut layouts);
+
+    match layouts.remove(id) {
+        Some(_) => {
+            // the layouts inlining it shrink, removing one can't close a cycle
+            let _ = compute_sizes(layouts);
+            true
+        },
+        None => false,
+    }
+}
+
+/// Decodes the layout at `address` into a json tree, following pointers `max_depth` levels deep.
+pub fn read_struct(target: &IMemoryTarget, address: usize, id: &str, max_depth: usize) -> Result<Value, MemoryError> {
+    let layouts = LAYOUTS.lock().clone();
+    let layout = layouts.get(id)
+        .ok_or(MemoryError::LayoutNotFound)?;
+
+    let reader = Reader { target, layouts: &layouts };
+    reader.read_layout(layout, address, max_depth)
+        .ok_or(MemoryError::FailedtoReadProcessMemory)
+}
+
+struct Reader<'a> {
+    target: &'a IMemoryTarget,
+    layouts: &'a HashMap<String, Layout>,
+}
+
+impl<'a> Reader<'a> {
+    fn read_layout(&self, layout: &Layout, address: usize, depth: usize) -> Option<Value> {
+        let size = layout.byte_size;
+        let buffer = self.target.read_bytes(address, size);
+        if buffer.len() != size {
+            return None;
+        }
+
+        let mut object = Map::new();
+        for field in layout.fields.iter() {
+            let value = match field.count {
+                0 => self.read_field(field, &buffer, address, field.offset, depth),
+                count => {
+                    let stride = field.element_size(self.layouts);
+                    Value::Array((0..count)
+                        .map(|i| self.read_field(field, &buffer, address, field.offset + i * stride, depth))
+                        .collect())
+                }
+            };
+
+            object.insert(field.name.clone(), value);
+        }
+
+        Some(Value::Object(object))
+    }
+
+    fn read_field(&self, field: &Field, buffer: &[u8], base: usize, offset: usize, depth: usize) -> Value {
+        let bytes = &buffer[offset.min(buffer.len())..];
+
+        macro_rules! int {
+            ($t:ty) => {
+                bytes.get(..std::mem::size_of::<$t>())
+                    .map_or(Value::Null, |b| json!(<$t>::from_le_bytes(b.try_into().unwrap())))
+            };
+        }
+
+        match field.field_type {
+            FieldType::I8 => int!(i8),
+            FieldType::U8 => int!(u8),
+            FieldType::I16 => int!(i16),
+            FieldType::U16 => int!(u16),
+            FieldType::I32 => int!(i32),
+            FieldType::U32 => int!(u32),
+            FieldType::I64 => int!(i64),
+            FieldType::U64 => int!(u64),
+            FieldType::F32 => int!(f32),
+            FieldType::F64 => int!(f64),
+            FieldType::Bool => bytes.first().map_or(Value::Null, |b| json!(*b != 0)),
+            FieldType::Pointer => {
+                let pointer = match field.length {
+                    4 => bytes.get(..4).map(|b| u32::from_le_bytes(b.try_into().unwrap()) as u64),
+                    _ => bytes.get(..8).map(|b| u64::from_le_bytes(b.try_into().unwrap())),
+                };
+
+                let Some(pointer) = pointer else {
+                    return Value::Null;
+                };
+
+                let value = match (field.target.as_ref().and_then(|target| self.layouts.get(target)), pointer, depth) {
+                    (Some(layout), pointer, depth) if pointer != 0 && depth > 0 => self.read_layout(layout, pointer as usize, depth - 1)
+                        .unwrap_or(Value::Null),
+                    _ => Value::Null,
+                };
+
+                json!({ "address": pointer, "value": value })
+            },
+            FieldType::Struct => field.target.as_ref()
+                .and_then(|target| self.layouts.get(target))
+                .and_then(|layout| self.read_layout(layout, base + offset, depth))
+                .unwrap_or(Value::Null),
+            FieldType::CString => {
+                let raw = &bytes[..field.length.min(bytes.len())];
+                let end = raw.iter().position(|b| *b == 0).unwrap_or(raw.len());
+                json!(String::from_utf8_lossy(&raw[..end]))
+            },
+            FieldType::WString => {
+                let units = bytes[..(field.length * 2).min(bytes.len())]
+                    .chunks_exact(2)
+                    .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                    .take_while(|unit| *unit != 0)
+                    .collect::<Vec<_>>();
+                json!(String::from_utf16_lossy(&units))
+            },
+            FieldType::Bytes => json!(bytes[..field.length.min(bytes.len())].to_vec()),
+        }
+    }
+}
@@ -1,32 +1,80 @@
 #![allow(dead_code, unused_variables)]
 #![feature(try_trait_v2)]
 
-use std::{sync::atomic::{AtomicBool, Ordering}, time::Instant};
+use std::{ffi::{c_char, CString}, sync::Arc, time::Instant};
 
-use definitions::{ByteBuffer, CallResult, I18n, ITarget, TargetHandle};
-use error::MemoryError;
+use definitions::{ByteBuffer, CallResult, I18n, IMemoryTarget, TargetHandle};
 use log::{info, warn, LevelFilter};
 use once_cell::sync::Lazy;
 use lazy_static::lazy_static;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use rust_i18n::error::AsDetails;
-use udbg::{memory::{MemoryPage, ReadMemoryUtils}, pe::*, target::{TargetUtil, UDbgTarget}};
 
-use crate::definitions::{CMemoryPageInfo, EngineHandleArc, TargetsArcM};
+use crate::{
+    definitions::{CMemoryPageInfo, CWriteResult, TargetsArcM},
+    disasm::CInstruction,
+    float::{CFloatMatch, CFloatQuery},
+    guess::CGuessedSlot,
+    helpers::like::CStringLike,
+    patch::CPatchInfo,
+    pattern::{CCaptureMatch, CResolvedMatch},
+    regex_scan::CRegexMatch,
+    text::CStringMatch,
+};
 
 pub use convert_case::*;
 pub use rust_i18n::{t, t_add};
 
+// Native api, the extern "C" functions below are a thin layer over it
+pub use crate::{
+    asm::assemble as assemble_code,
+    ct::import as import_cheat_table,
+    definitions::{MemoryTarget, MockPage, MockTarget, PageFilter, WriteMechanism},
+    disasm::Instruction,
+    error::MemoryError,
+    expr::Expression,
+    float::{FloatMatcher, FloatMode, FloatType},
+    guess::{Slot, SlotKind},
+    layout::{load as load_layouts, unload as unload_layout},
+    patch::{Patch, PatchId},
+    pattern::{Capture, CaptureMatch},
+    process::{Process, Region},
+    scanner::Scanner,
+    symbol::Symbol,
+    script::{load as load_script, run as run_script, ScriptError},
+    table::{load as load_table, unload as unload_table, AddressSpec, Entry as TableEntry, EntryValue, FreezeValue, Table, TableId, ValueType},
+    text::TextEncoding,
+};
+
 mod definitions;
 mod helpers;
 mod error;
+mod process;
+mod scanner;
+mod patch;
+mod disasm;
+mod asm;
+mod signature;
+mod pattern;
+mod text;
+mod regex_scan;
+mod float;
+mod layout;
+mod guess;
+mod abi;
+mod script;
+mod table;
+mod ct;
+mod expr;
+mod symbol;
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+mod remote;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "node")]
+mod node;
 
 rust_i18n::i18n!("locales", backend = I18n::new());
 
-lazy_static! {
-    static ref ENGINE: Lazy<EngineHandleArc> = Lazy::new(|| EngineHandleArc::default());
-}
-
 lazy_static! {
     static ref TARGETS: Lazy<TargetsArcM> = Lazy::new(|| TargetsArcM::new(Vec::new().into()));
 }
@@ -34,91 +82,246 @@ lazy_static! {
 #[no_mangle] 
 pub extern "C" fn attach(pid: u32) -> CallResult<usize> {
     let mut collection = TARGETS.lock();
-    
-    match collection.iter().filter(|n| n.pid() == pid).next() {
-        Some(_) => {    
+
+    match collection.iter().flatten().find(|n| n.pid() == pid) {
+        Some(_) => {
             warn!("failed to attach the process with id {:?}. already attached.", pid);
             CallResult::from(MemoryError::ProcessAlreadyAttached.as_details())
         },
         None => {
-            let mut engine = ENGINE.lock();
-            let target = engine.open(pid).unwrap();  
-            collection.push(target);
+            let process = match Process::attach(pid) {
+                Ok(process) => process,
+                Err(err) => {
+                    warn!("failed to attach the process with id {:?}: {:?}", pid, err);
+                    return CallResult::from(err.as_details());
+                }
+            };
 
+            collection.push(Some(process.target().clone()));
             let target_handle = collection.len() - 1;
 
             info!("attached the process with id {:?}, handle: {:?}", pid, target_handle);
             CallResult::new(Some(target_handle), None)
-        } 
+        }
     }
 }
 
 #[no_mangle] 
 pub extern "C" fn detach(pid: u32) -> CallResult<bool> {
     let mut collection = TARGETS.lock();
-    let found = AtomicBool::new(false);
 
-    collection.retain(|n| {
-        if n.pid() == pid {
-            _ = n.detach();
-            found.store(true, Ordering::Relaxed);
-            return false;
-        }
-        
-        true
-    });
+    // the slot is left empty, handles of the other targets are indices and have to stay valid
+    let slot = collection.iter_mut()
+        .find(|slot| slot.as_ref().is_some_and(|n| n.pid() == pid));
 
-    match found.load(Ordering::Relaxed) {
-        false => {
+    match slot.and_then(Option::take) {
+        Some(target) => {
+            Process::from_target(target).detach();
+            info!("detached the process #{:?}.", pid);
+            CallResult::new(Some(1), None)
+        },
+        None => {
             warn!("failed to detach the process #{:?}. not attached.", pid);
             CallResult::from(MemoryError::ProcessNotAttached.as_details())
+        }
+    }
+}
+
+/// Searches an AOB pattern (`48 8B 05 ?? ?? ?? ??`) over the pages matching `filter`.
+#[no_mangle] 
+pub unsafe extern "C" fn aob_query(target_handle: TargetHandle, pattern_buffer: ByteBuffer, filter: PageFilter, alignment: usize) -> CallResult<Vec<usize>> {
+    let process = match validate_target_handle(target_handle) {
+        Ok(process) => process,
+        Err(err) => return err.into(),
+    };
+
+    let pattern = pattern_buffer.into_string();
+
+    info!("searching for pattern: {:?}", pattern);
+    let start_time = Instant::now();
+
+    match process.scanner().filter(filter).alignment(alignment).aob(&pattern) {
+        Ok(addresses) => {
+            info!("search is completed. found {:?} occurrences in total. took {:?}", addresses.len(), start_time.elapsed());
+            addresses.into()
         },
-        true => {
-            info!("detached the process #{:?}.", pid);
-            CallResult::new(Some(1), None)
+        Err(err) => {
+            warn!("failed to search for pattern {:?}: {:?}", pattern, err);
+            CallResult::from(err.as_details())
         }
     }
 }
 
+/// `aob_query` that also resolves the first `( .. )` group of the pattern for every match, e.g.
+/// `48 8B 05 (?? ?? ?? ??)` yields the global the rip relative operand points to. Matches whose
+/// group can't be resolved are kept with `resolved` unset.
 #[no_mangle] 
-pub unsafe extern "C" fn aob_query(target_handle: TargetHandle, pattern_buffer: ByteBuffer, mapped: bool, readable: bool, writable: bool, executable: bool) -> CallResult<Vec<usize>> {
-    let target_opt = validate_target_handle(target_handle);
-    if target_opt.is_err() {
-        return CallResult::from(target_opt.err().unwrap());
+pub unsafe extern "C" fn aob_query_resolve(target_handle: TargetHandle, pattern_buffer: ByteBuffer, filter: PageFilter, alignment: usize, bitness: u32) -> CallResult<Vec<CResolvedMatch>> {
+    let process = match validate_target_handle(target_handle) {
+        Ok(process) => process,
+        Err(err) => return err.into(),
+    };
+
+    let pattern = pattern_buffer.into_string();
+    let start_time = Instant::now();
+
+    match process.scanner().filter(filter).alignment(alignment).aob_resolve(&pattern, bitness) {
+        Ok(matches) => {
+            info!("resolving search is completed. found {:?} occurrences in total. took {:?}", matches.len(), start_time.elapsed());
+            matches.into_iter()
+                .map(|(address, target)| CResolvedMatch { address, target: target.unwrap_or_default(), resolved: target.is_some() })
+                .collect::<Vec<_>>()
+                .into()
+        },
+        Err(err) => {
+            warn!("failed to resolve pattern {:?}: {:?}", pattern, err);
+            CallResult::from(err.as_details())
+        }
     }
-    
-    let target = target_opt.unwrap();
+}
+
+/// `aob_query` returning, for every match, the value of each `( .. )` / `(name: .. )` group
+/// decoded as a little-endian integer of the group width (1 to 8 bytes).
+#[no_mangle] 
+pub unsafe extern "C" fn aob_query_capture(target_handle: TargetHandle, pattern_buffer: ByteBuffer, filter: PageFilter, alignment: usize) -> CallResult<Vec<CCaptureMatch>> {
+    let process = match validate_target_handle(target_handle) {
+        Ok(process) => process,
+        Err(err) => return err.into(),
+    };
+
     let pattern = pattern_buffer.into_string();
-    let pages = filter_pages(&target, mapped, readable, writable, executable);
-    let pattern = parse_pattern(&pattern);
+    let start_time = Instant::now();
 
-    info!("searching for pattern: {:?}", pattern);
+    match process.scanner().filter(filter).alignment(alignment).aob_capture(&pattern) {
+        Ok(matches) => {
+            info!("capturing search is completed. found {:?} occurrences in total. took {:?}", matches.len(), start_time.elapsed());
+            matches.iter()
+                .map(|capture_match| CCaptureMatch::from(capture_match))
+                .collect::<Vec<_>>()
+                .into()
+        },
+        Err(err) => {
+            warn!("failed to capture pattern {:?}: {:?}", pattern, err);
+            CallResult::from(err.as_details())
+        }
+    }
+}
+
+/// Searches `text` in every encoding flagged in `encodings` (1 ascii, 2 utf-8, 4 utf-16le) at
+/// once and returns the matches sorted by address. A match is reported for each flagged encoding
+/// it is valid in, ASCII text is found as both ascii and utf-8.
+#[no_mangle] 
+pub unsafe extern "C" fn string_scan(target_handle: TargetHandle, text_buffer: ByteBuffer, encodings: u32, case_insensitive: bool, filter: PageFilter, alignment: usize) -> CallResult<Vec<CStringMatch>> {
+    let process = match validate_target_handle(target_handle) {
+        Ok(process) => process,
+        Err(err) => return err.into(),
+    };
+
+    let text = text_buffer.into_string();
+
+    info!("searching for string: {:?}, encodings: {:#x}, case insensitive: {:?}", text, encodings, case_insensitive);
+    let start_time = Instant::now();
+
+    match process.scanner().filter(filter).alignment(alignment).string(&text, encodings, case_insensitive) {
+        Ok(matches) => {
+            info!("string search is completed. found {:?} occurrences in total. took {:?}", matches.len(), start_time.elapsed());
+            matches.into_iter()
+                .map(|(address, encoding)| CStringMatch { address, encoding })
+                .collect::<Vec<_>>()
+                .into()
+        },
+        Err(err) => {
+            warn!("failed to search for {:?} with encodings {:#x}: {:?}", text, encodings, err);
+            CallResult::from(err.as_details())
+        }
+    }
+}
+
+/// Runs a bytes regex over the filtered pages. Longer matches than `max_match_len` are reported
+/// with their length truncated to it (0 picks a default), `max_matches` keeps only the lowest
+/// addresses (0 for no cap).
+#[no_mangle] 
+pub unsafe extern "C" fn regex_scan(target_handle: TargetHandle, pattern_buffer: ByteBuffer, max_match_len: usize, max_matches: usize, filter: PageFilter, alignment: usize) -> CallResult<Vec<CRegexMatch>> {
+    let process = match validate_target_handle(target_handle) {
+        Ok(process) => process,
+        Err(err) => return err.into(),
+    };
+
+    let pattern = pattern_buffer.into_string();
+
+    info!("searching for regex: {:?}", pattern);
+    let start_time = Instant::now();
+
+    match process.scanner().filter(filter).alignment(alignment).regex(&pattern, max_match_len, max_matches) {
+        Ok(matches) => {
+            info!("regex search is completed. found {:?} occurrences in total. took {:?}", matches.len(), start_time.elapsed());
+            matches.into_iter()
+                .map(|(address, length)| CRegexMatch { address, length })
+                .collect::<Vec<_>>()
+                .into()
+        },
+        Err(err) => {
+            warn!("invalid regex supplied: {:?}", pattern);
+            CallResult::from(err.as_details())
+        }
+    }
+}
+
+/// Searches f32/f64 values matching `query` (rounded, truncated or within epsilon) over the
+/// filtered pages.
+#[no_mangle] 
+pub unsafe extern "C" fn float_scan(target_handle: TargetHandle, query: CFloatQuery, filter: PageFilter) -> CallResult<Vec<CFloatMatch>> {
+    let process = match validate_target_handle(target_handle) {
+        Ok(process) => process,
+        Err(err) => return err.into(),
+    };
+
+    let matcher = match FloatMatcher::try_from(&query) {
+        Ok(matcher) => matcher,
+        Err(err) => return CallResult::from(err.as_details()),
+    };
+
+    info!("searching for float: {:?}", matcher);
     let start_time = Instant::now();
 
-    let addresses: Vec<usize> = pages
-        .par_iter()
-        .flat_map_iter(|page| {
-            let buff = target.read_bytes(page.base, page.size);
-            find_all_occurrences(&buff, &pattern)
-                .into_iter()
-                .map(move |addr| page.base + addr)
-        })
-        .collect();
+    let matches = process.scanner().filter(filter).float(&matcher)
+        .into_iter()
+        .map(|(address, value)| CFloatMatch { address, value })
+        .collect::<Vec<_>>();
+
+    info!("float search is completed. found {:?} occurrences in total. took {:?}", matches.len(), start_time.elapsed());
+
+    matches.into()
+}
+
+/// `float_scan` over a host supplied list of candidate addresses (`usize`s).
+#[no_mangle] 
+pub unsafe extern "C" fn float_scan_candidates(target_handle: TargetHandle, query: CFloatQuery, candidates_buffer: ByteBuffer) -> CallResult<Vec<CFloatMatch>> {
+    let process = match validate_target_handle(target_handle) {
+        Ok(process) => process,
+        Err(err) => return err.into(),
+    };
 
-    let end_time = Instant::now();
-    let duration = end_time - start_time;
-    info!("search is completed. found {:?} occurrences in total. took {:?}", addresses.len(), duration);
+    let matcher = match FloatMatcher::try_from(&query) {
+        Ok(matcher) => matcher,
+        Err(err) => return CallResult::from(err.as_details()),
+    };
 
-    addresses.into()
+    let candidates = candidates_buffer.into_sized_slice::<usize>();
+    process.scanner().float_candidates(&matcher, candidates)
+        .into_iter()
+        .map(|(address, value)| CFloatMatch { address, value })
+        .collect::<Vec<_>>()
+        .into()
 }
 
 #[no_mangle] 
 pub unsafe extern "C" fn collect_pages(target: TargetHandle) -> CallResult<Vec<CMemoryPageInfo>> {
     match  validate_target_handle(target) {
-        Ok(target) => {
-            let pages = target.collect_memory_info()
+        Ok(process) => {
+            let pages = process.regions()
                 .iter()
-                .map(|page| CMemoryPageInfo::from(page))
+                .map(|region| CMemoryPageInfo::from(region.page()))
                 .collect::<Vec<CMemoryPageInfo>>();
 
             pages.into()
@@ -132,108 +335,767 @@ pub unsafe extern "C" fn collect_pages(target: TargetHandle) -> CallResult<Vec<C
 #[no_mangle] 
 pub unsafe extern "C" fn write_memory(target: TargetHandle, address: usize, buffer_repr: ByteBuffer) -> CallResult<usize> {
     match  validate_target_handle(target) {
-        Ok(target) => {
+        Ok(process) => {
             let buffer = buffer_repr.into_slice();
-            let written = target.write_memory(address, buffer)
-                .and_then(|bytes| {
-                    if bytes == 0 {
-                        return None;
-                    }
+            CallResult::new(process.write(address, buffer).ok(), None)
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `write_memory` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn write_memory_expr(target: TargetHandle, address_repr: ByteBuffer, buffer_repr: ByteBuffer) -> CallResult<usize> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => write_memory(target, address, buffer_repr),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
 
-                    Some(bytes)
-                });
+/// `write_memory` with an opt-in `force` mode that writes to read-only and executable pages too.
+#[no_mangle] 
+pub unsafe extern "C" fn write_memory_ex(target: TargetHandle, address: usize, buffer_repr: ByteBuffer, force: bool) -> CallResult<CWriteResult> {
+    match  validate_target_handle(target) {
+        Ok(process) => {
+            let buffer = buffer_repr.into_slice();
 
-            CallResult::new(written, None)
+            match process.write_with(address, buffer, force) {
+                Ok((written, mechanism)) => {
+                    info!("wrote {:?} bytes at {:#x} using {:?}", written, address, mechanism);
+                    let result = CWriteResult { written, mechanism };
+                    CallResult::new(Some(Box::into_raw(Box::new(result)) as usize), None)
+                },
+                Err(err) => {
+                    warn!("failed to write {:?} bytes at {:#x}", buffer.len(), address);
+                    CallResult::from(err.as_details())
+                }
+            }
         },
         Err(err) => {
             err.into()
         }
-    }  
+    }
+}
+
+/// `write_memory_ex` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn write_memory_ex_expr(target: TargetHandle, address_repr: ByteBuffer, buffer_repr: ByteBuffer, force: bool) -> CallResult<CWriteResult> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => write_memory_ex(target, address, buffer_repr, force),
+        Err(err) => CallResult::from(err.as_details())
+    }
 }
 
 #[no_mangle] 
 pub unsafe extern "C" fn write_bytes(target: TargetHandle, address: usize, buffer_repr: ByteBuffer) -> usize {
     match  validate_target_handle(target) {
-        Ok(target) => {
+        Ok(process) => {
             let buffer = buffer_repr.into_slice();
-            let written = target.write_memory(address, buffer).unwrap_or_default();
-            written
+            process.write(address, buffer).unwrap_or_default()
         },
         Err(err) => {
             0
         }
-    }  
+    }
+}
+
+/// `write_bytes` at an address expression, see `evaluate_expression`. Returns 0 when it fails to
+/// evaluate.
+#[no_mangle]
+pub unsafe extern "C" fn write_bytes_expr(target: TargetHandle, address_repr: ByteBuffer, buffer_repr: ByteBuffer) -> usize {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => write_bytes(target, address, buffer_repr),
+        Err(_) => 0
+    }
 }
 
 #[no_mangle] 
-pub unsafe 
+pub unsafe
 extern "C" fn read_bytes(target: TargetHandle, address: usize, size: usize) -> CallResult<Vec<u8>> {
     match validate_target_handle(target) {
-        Ok(target) => {           
-            let mut buffer: Vec<u8> = vec![0u8; size];
-            let len = match target.read_memory(address, &mut buffer) {
-                Some(slice) => slice.len(),
-                None => 0,
-            };
-            buffer.resize(len, 0);
-
+        Ok(process) => {
+            let buffer = process.read(address, size);
             CallResult::new(Some(ByteBuffer::from_vec(buffer).into_raw() as usize), None)
         },
         Err(err) => {
             err.into()
         }
-    } 
+    }
+}
+
+/// `read_bytes` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn read_bytes_expr(target: TargetHandle, address_repr: ByteBuffer, size: usize) -> CallResult<Vec<u8>> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => read_bytes(target, address, size),
+        Err(err) => CallResult::from(err.as_details())
+    }
 }
 
 #[no_mangle] 
-pub unsafe 
+pub unsafe
 extern "C" fn read_memory<'a>(target: TargetHandle, address: usize, destination: *mut u8, size: usize) -> usize {
     match validate_target_handle(target) {
-        Ok(target) => {           
-            let buffer = target.read_bytes(address, size);
-            std::ptr::copy_nonoverlapping(buffer.as_ptr(), destination, size);
+        Ok(process) => {
+            let buffer = process.read(address, size);
+            std::ptr::copy_nonoverlapping(buffer.as_ptr(), destination, buffer.len());
             buffer.len()
         },
         Err(err) => {
             0
         }
-    } 
+    }
 }
 
-fn filter_pages(target: &Arc<dyn UDbgTarget>, mapped: bool, readable: bool, writable: bool, executable: bool) -> Vec<MemoryPage> {
-    let pages = target.collect_memory_info();
-    let collected = pages
-        .iter()
-        .filter(|page| {
-            let is_valid = page.state == MEM_COMMIT
-            && page.base < 140737488355327usize // todo use address space, eg: sysinfo in win32
-            && (page.protect & PAGE_GUARD) == 0
-            && (page.protect & PAGE_NOACCESS) == 0
-            && (page.type_ == MEM_PRIVATE || page.type_ == MEM_IMAGE)
-            && (!mapped || page.type_ == MEM_MAPPED);
-    
-            if !is_valid {
-                return false
+/// `read_memory` at an address expression, see `evaluate_expression`. Returns 0 when it fails to
+/// evaluate.
+#[no_mangle]
+pub unsafe extern "C" fn read_memory_expr(target: TargetHandle, address_repr: ByteBuffer, destination: *mut u8, size: usize) -> usize {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => read_memory(target, address, destination, size),
+        Err(_) => 0
+    }
+}
+
+#[no_mangle] 
+pub unsafe extern "C" fn allocate_memory(target: TargetHandle, size: usize, protection: u32, near_address: usize) -> CallResult<usize> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match process.allocate(size, protection, near_address) {
+                Ok(address) => {
+                    info!("allocated {:?} bytes at {:#x} in the process #{:?}", size, address, process.pid());
+                    CallResult::new(Some(address), None)
+                },
+                Err(err) => {
+                    warn!("failed to allocate {:?} bytes near {:#x} in the process #{:?}", size, near_address, process.pid());
+                    CallResult::from(err.as_details())
+                }
             }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
 
-            let is_readable = (page.protect & PAGE_READONLY) > 0;
-        
-            let is_writable = (page.protect & PAGE_READWRITE) > 0
-                || (page.protect & PAGE_WRITECOPY) > 0
-                || (page.protect & PAGE_EXECUTE_READWRITE) > 0
-                || (page.protect & PAGE_EXECUTE_WRITECOPY) > 0;
-    
-            let is_executable = (page.protect & PAGE_EXECUTE) > 0
-                || (page.protect & PAGE_EXECUTE_READ) > 0
-                || (page.protect & PAGE_EXECUTE_READWRITE) > 0
-                || (page.protect & PAGE_EXECUTE_WRITECOPY) > 0;
-        
-            is_valid && (is_readable && readable || is_writable && writable || is_executable && executable)
-        })
-        .cloned()
-        .collect::<Vec<_>>();
+/// `allocate_memory` near an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn allocate_memory_expr(target: TargetHandle, size: usize, protection: u32, near_address_repr: ByteBuffer) -> CallResult<usize> {
+    match resolve_address(target, near_address_repr, 0) {
+        Ok(near_address) => allocate_memory(target, size, protection, near_address),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+#[no_mangle] 
+pub unsafe extern "C" fn free_memory(target: TargetHandle, address: usize, size: usize) -> CallResult<bool> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match process.free(address, size) {
+                Ok(_) => CallResult::new(Some(1), None),
+                Err(err) => {
+                    warn!("failed to free memory at {:#x} in the process #{:?}", address, process.pid());
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `free_memory` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn free_memory_expr(target: TargetHandle, address_repr: ByteBuffer, size: usize) -> CallResult<bool> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => free_memory(target, address, size),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+/// Changes the protection of the pages covering the range, returns the previous protection of the
+/// first page.
+#[no_mangle] 
+pub unsafe extern "C" fn protect_memory(target: TargetHandle, address: usize, size: usize, new_protection: u32) -> CallResult<u32> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match process.protect(address, size, new_protection) {
+                Ok(old_protection) => CallResult::new(Some(old_protection as usize), None),
+                Err(err) => {
+                    warn!("failed to change protection of {:#x} in the process #{:?}", address, process.pid());
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `protect_memory` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn protect_memory_expr(target: TargetHandle, address_repr: ByteBuffer, size: usize, new_protection: u32) -> CallResult<u32> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => protect_memory(target, address, size, new_protection),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+/// Overwrites the bytes at `address`, writing to read-only and executable pages too, and saves the
+/// original bytes for `patch_restore`. The current bytes have to equal `expected` unless it is
+/// empty, an empty `expected` skips the check.
+#[no_mangle] 
+pub unsafe extern "C" fn patch_apply(target: TargetHandle, address: usize, buffer_repr: ByteBuffer, expected_repr: ByteBuffer) -> CallResult<PatchId> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            let bytes = buffer_repr.into_slice();
+            let expected: &[u8] = match expected_repr.len() {
+                0 => &[],
+                _ => expected_repr.into_slice(),
+            };
+
+            match process.apply_patch(address, bytes, expected) {
+                Ok(id) => {
+                    info!("applied the patch #{:?} at {:#x}, {:?} bytes", id, address, bytes.len());
+                    CallResult::new(Some(id), None)
+                },
+                Err(err) => {
+                    warn!("failed to apply the patch at {:#x}: {:?}", address, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `patch_apply` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn patch_apply_expr(target: TargetHandle, address_repr: ByteBuffer, buffer_repr: ByteBuffer, expected_repr: ByteBuffer) -> CallResult<PatchId> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => patch_apply(target, address, buffer_repr, expected_repr),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+#[no_mangle] 
+pub unsafe extern "C" fn patch_restore(target: TargetHandle, id: PatchId) -> CallResult<bool> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match process.restore_patch(id) {
+                Ok(_) => {
+                    info!("restored the patch #{:?}", id);
+                    CallResult::new(Some(1), None)
+                },
+                Err(err) => {
+                    warn!("failed to restore the patch #{:?}: {:?}", id, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+#[no_mangle] 
+pub unsafe extern "C" fn patch_restore_all(target: TargetHandle) -> CallResult<usize> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            CallResult::new(Some(process.restore_all_patches()), None)
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+#[no_mangle] 
+pub unsafe extern "C" fn patch_list(target: TargetHandle) -> CallResult<Vec<CPatchInfo>> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            process.patches()
+                .iter()
+                .map(|patch| CPatchInfo::from(patch))
+                .collect::<Vec<CPatchInfo>>()
+                .into()
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// Registers an already opened target, e.g. a `MockTarget`, and returns its handle.
+pub fn insert_target(target: Arc<IMemoryTarget>) -> TargetHandle {
+    let mut collection = TARGETS.lock();
+    collection.push(Some(target));
+    collection.len() - 1
+}
+
+/// Assembles newline or `;` separated intel syntax instructions (`mov eax, 1; ret`) for `address`
+/// without writing them, `//` starts a comment.
+#[no_mangle] 
+pub unsafe extern "C" fn assemble(address: usize, source_repr: ByteBuffer, length: usize, bitness: u32) -> CallResult<Vec<u8>> {
+    let source = source_repr.into_string();
+
+    match asm::assemble(&source, address, length, bitness) {
+        Ok(bytes) => bytes.into(),
+        Err(err) => {
+            warn!("failed to assemble {:?}: {:?}", source, err);
+            CallResult::from(err.as_details())
+        }
+    }
+}
+
+/// Assembles the instructions relative to `address`, pads them with NOPs up to `length` when it
+/// is not 0 and writes them the same way `write_memory_ex` does.
+#[no_mangle] 
+pub unsafe extern "C" fn assemble_and_write(target: TargetHandle, address: usize, source_repr: ByteBuffer, length: usize, bitness: u32, force: bool) -> CallResult<usize> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            let source = source_repr.into_string();
+
+            match process.assemble_and_write(address, &source, length, bitness, force) {
+                Ok((written, mechanism)) => {
+                    info!("assembled and wrote {:?} bytes at {:#x} using {:?}", written, address, mechanism);
+                    CallResult::new(Some(written), None)
+                },
+                Err(err) => {
+                    warn!("failed to assemble and write {:?} at {:#x}: {:?}", source, address, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `assemble_and_write` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn assemble_and_write_expr(target: TargetHandle, address_repr: ByteBuffer, source_repr: ByteBuffer, length: usize, bitness: u32, force: bool) -> CallResult<usize> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => assemble_and_write(target, address, source_repr, length, bitness, force),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+/// Disassembles `count` x86 instructions at `address`. `bitness` is 16, 32 or 64, anything else
+/// picks the bitness of the library.
+#[no_mangle] 
+pub unsafe extern "C" fn disassemble(target: TargetHandle, address: usize, count: usize, bitness: u32) -> CallResult<Vec<CInstruction>> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            process.disassemble(address, count, bitness)
+                .iter()
+                .map(|instruction| CInstruction::from(instruction))
+                .collect::<Vec<CInstruction>>()
+                .into()
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `disassemble` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn disassemble_expr(target: TargetHandle, address_repr: ByteBuffer, count: usize, bitness: u32) -> CallResult<Vec<CInstruction>> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => disassemble(target, address, count, bitness),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+/// Generates an `aob_query` pattern of at most `max_len` bytes (capped at 256) starting at `address`
+/// that is unique within the module of the address. Returns the pattern as a C string.
+#[no_mangle] 
+pub unsafe extern "C" fn make_signature(target: TargetHandle, address: usize, max_len: usize, bitness: u32) -> CallResult<*const c_char> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match process.make_signature(address, max_len, bitness) {
+                Ok(pattern) => {
+                    info!("generated signature for {:#x}: {:?}", address, pattern);
+                    CallResult::new(Some(pattern.as_c_char_ptr() as usize), None)
+                },
+                Err(err) => {
+                    warn!("failed to generate a signature for {:#x}: {:?}", address, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `make_signature` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn make_signature_expr(target: TargetHandle, address_repr: ByteBuffer, max_len: usize, bitness: u32) -> CallResult<*const c_char> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => make_signature(target, address, max_len, bitness),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+/// Registers the layouts of a json (`format` 0) or toml (`format` 1) schema. Returns the number
+/// of registered layouts, fails when layouts inline each other in a cycle or a pointer `length`
+/// is not 4 or 8.
+#[no_mangle]
+pub unsafe extern "C" fn layout_load(schema_repr: ByteBuffer, format: u32) -> CallResult<usize> {
+    match layout::load(&schema_repr.into_string(), format) {
+        Ok(count) => {
+            info!("loaded {:?} layouts", count);
+            CallResult::new(Some(count), None)
+        },
+        Err(err) => {
+            warn!("failed to load layouts: {:?}", err);
+            CallResult::from(err.as_details())
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn layout_unload(id_repr: ByteBuffer) -> CallResult<bool> {
+    let id = id_repr.into_string();
+
+    match layout::unload(&id) {
+        true => CallResult::new(Some(1), None),
+        false => CallResult::from(MemoryError::LayoutNotFound.as_details())
+    }
+}
+
+/// Decodes the struct at `address` using the registered layout `layout_id`, following pointers
+/// `max_depth` levels deep. Returns the decoded tree as a json C string.
+#[no_mangle]
+pub unsafe extern "C" fn read_struct(target: TargetHandle, address: usize, layout_id_repr: ByteBuffer, max_depth: usize) -> CallResult<*const c_char> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            let layout_id = layout_id_repr.into_string();
+
+            match process.read_struct(address, &layout_id, max_depth) {
+                Ok(value) => CallResult::new(Some(value.to_string().as_c_char_ptr() as usize), None),
+                Err(err) => {
+                    warn!("failed to read {:?} at {:#x}: {:?}", layout_id, address, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `read_struct` at an address expression, see `evaluate_expression`.
+#[no_mangle]
+pub unsafe extern "C" fn read_struct_expr(target: TargetHandle, address_repr: ByteBuffer, layout_id_repr: ByteBuffer, max_depth: usize) -> CallResult<*const c_char> {
+    match resolve_address(target, address_repr, 0) {
+        Ok(address) => read_struct(target, address, layout_id_repr, max_depth),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+/// Classifies every 4/8 byte slot of the `size` bytes at `address` as zero, small int, float,
+/// module, heap or string pointer, validating pointers against the page map. `pointer_size` is
+/// the one of the target, 4 or 8, 0 picks the native size.
+#[no_mangle]
+pub unsafe extern "C" fn guess_struct(target: TargetHandle, address: usize, size: usize, pointer_size: usize) -> CallResult<Vec<CGuessedSlot>> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match process.guess_struct(address, size, pointer_size) {
+                Ok(slots) => slots.iter()
+                    .map(|slot| CGuessedSlot::from(slot))
+                    .collect::<Vec<CGuessedSlot>>()
+                    .into(),
+                Err(err) => {
+                    warn!("failed to guess the struct at {:#x}: {:?}", address, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// `guess_struct` at an address expression, see `evaluate_expression`. `[..]` in the expression
+/// reads `pointer_size` pointers too.
+#[no_mangle]
+pub unsafe extern "C" fn guess_struct_expr(target: TargetHandle, address_repr: ByteBuffer, size: usize, pointer_size: usize) -> CallResult<Vec<CGuessedSlot>> {
+    match resolve_address(target, address_repr, pointer_size) {
+        Ok(address) => guess_struct(target, address, size, pointer_size),
+        Err(err) => CallResult::from(err.as_details())
+    }
+}
+
+/// Runs a Lua script with the `rsmem` module loaded. Returns what the script printed as a C
+/// string, the error carries the Lua message and traceback when the script fails.
+#[no_mangle]
+pub unsafe extern "C" fn script_run(source_repr: ByteBuffer) -> CallResult<*const c_char> {
+    script_result(script::run(&source_repr.into_string(), "script"))
+}
+
+/// `script_run` for the script file at `path`.
+#[no_mangle]
+pub unsafe extern "C" fn script_load(path_repr: ByteBuffer) -> CallResult<*const c_char> {
+    let path = path_repr.into_string();
+    info!("running the script {:?}", path);
+
+    script_result(script::load(&path))
+}
+
+fn script_result(result: Result<String, ScriptError>) -> CallResult<*const c_char> {
+    match result {
+        Ok(output) => CallResult::new(Some(output.as_c_char_ptr() as usize), None),
+        Err(ScriptError::Memory(err)) => {
+            warn!("failed to run the script: {:?}", err);
+            CallResult::from(err.as_details())
+        },
+        Err(ScriptError::Lua(err)) => {
+            warn!("script failed: {}", err);
+            // `error("..")` takes any lua string, which may hold NULs
+            CallResult::new(None, Some(&err.to_string().replace('\0', "\u{fffd}")))
+        }
+    }
+}
+
+/// Registers a json (`format` 0) or toml (`format` 1) cheat table. Returns the table id.
+#[no_mangle]
+pub unsafe extern "C" fn table_load(source_repr: ByteBuffer, format: u32) -> CallResult<usize> {
+    match table::load(&source_repr.into_string(), format) {
+        Ok(id) => {
+            info!("loaded the table #{:?}", id);
+            CallResult::new(Some(id), None)
+        },
+        Err(err) => {
+            warn!("failed to load the table: {:?}", err);
+            CallResult::from(err.as_details())
+        }
+    }
+}
+
+/// Imports a Cheat Engine `.CT` file as a table, `pointer_size` is 4 or 8 (0 for the native size).
+/// Entries that can't be represented are skipped. Returns the table id.
+#[no_mangle]
+pub unsafe extern "C" fn table_import_ct(source_repr: ByteBuffer, pointer_size: usize) -> CallResult<usize> {
+    match ct::import(&source_repr.into_string(), pointer_size) {
+        Ok(table) => {
+            let count = table.entries.len();
+            let id = table::register(table);
+
+            info!("imported {:?} entries as the table #{:?}", count, id);
+            CallResult::new(Some(id), None)
+        },
+        Err(err) => {
+            warn!("failed to import the cheat table: {:?}", err);
+            CallResult::from(err.as_details())
+        }
+    }
+}
+
+/// Serializes the table `id` as json (`format` 0) or toml (`format` 1). Returns a C string.
+#[no_mangle]
+pub extern "C" fn table_save(id: usize, format: u32) -> CallResult<*const c_char> {
+    let text = table::get(id)
+        .ok_or(MemoryError::TableNotFound)
+        .and_then(|table| table.to_text(format));
+
+    match text {
+        Ok(text) => CallResult::new(Some(text.as_c_char_ptr() as usize), None),
+        Err(err) => {
+            warn!("failed to save the table #{:?}: {:?}", id, err);
+            CallResult::from(err.as_details())
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn table_unload(id: usize) -> CallResult<bool> {
+    match table::unload(id) {
+        true => CallResult::new(Some(1), None),
+        false => CallResult::from(MemoryError::TableNotFound.as_details())
+    }
+}
+
+/// Resolves every entry of the table `id`, writes the freeze values and reads the current ones.
+/// Returns a json array of `{ description, address, value, error }` as a C string, entries that
+/// fail to resolve or read carry the error instead of failing the call.
+#[no_mangle]
+pub extern "C" fn table_evaluate(target: TargetHandle, id: usize) -> CallResult<*const c_char> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match table::get(id) {
+                Some(table) => {
+                    match serde_json::to_string(&table.evaluate(&process)) {
+                        Ok(values) => CallResult::new(Some(values.as_c_char_ptr() as usize), None),
+                        Err(err) => {
+                            warn!("failed to serialize the values of the table #{:?}: {:?}", id, err);
+                            CallResult::from(MemoryError::InvalidTable.as_details())
+                        }
+                    }
+                },
+                None => CallResult::from(MemoryError::TableNotFound.as_details())
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// Evaluates an address expression such as `[[game.so+1F00]+18]+40`. Bare numbers are hex, `#`
+/// marks decimals, names are registered symbols or modules and `[..]` reads a `pointer_size`
+/// (4 or 8, 0 for the native size) pointer.
+#[no_mangle]
+pub unsafe extern "C" fn evaluate_expression(target: TargetHandle, expression_repr: ByteBuffer, pointer_size: usize) -> CallResult<usize> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            let expression = expression_repr.into_string();
+
+            match process.evaluate(&expression, pointer_size) {
+                Ok(address) => CallResult::new(Some(address), None),
+                Err(err) => {
+                    warn!("failed to evaluate {:?}: {:?}", expression, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// Names the address an expression evaluates to, for use in later expressions and table entries.
+/// A plain hex address works as an expression too. Returns the address.
+#[no_mangle]
+pub unsafe extern "C" fn symbol_register(target: TargetHandle, name_repr: ByteBuffer, expression_repr: ByteBuffer, pointer_size: usize) -> CallResult<usize> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            let name = name_repr.into_string();
+            let expression = expression_repr.into_string();
+
+            match process.define_symbol(&name, &expression, pointer_size) {
+                Ok(address) => CallResult::new(Some(address), None),
+                Err(err) => {
+                    warn!("failed to register the symbol {:?} as {:?}: {:?}", name, expression, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn symbol_lookup(target: TargetHandle, name_repr: ByteBuffer) -> CallResult<usize> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match process.symbol(&name_repr.into_string()) {
+                Some(address) => CallResult::new(Some(address), None),
+                None => CallResult::from(MemoryError::SymbolNotFound.as_details())
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// Returns the symbols of the target as a json array of `{ name, address, expression,
+/// pointer_size }` C string in registration order, addresses are `"0x.."` strings.
+#[no_mangle]
+pub extern "C" fn symbol_list(target: TargetHandle) -> CallResult<*const c_char> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match serde_json::to_string(&process.symbols()) {
+                Ok(symbols) => CallResult::new(Some(symbols.as_c_char_ptr() as usize), None),
+                Err(err) => {
+                    warn!("failed to serialize the symbols: {:?}", err);
+                    CallResult::from(MemoryError::FailedToWriteSymbols.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn symbol_remove(target: TargetHandle, name_repr: ByteBuffer) -> CallResult<bool> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            match process.remove_symbol(&name_repr.into_string()) {
+                true => CallResult::new(Some(1), None),
+                false => CallResult::from(MemoryError::SymbolNotFound.as_details())
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// Writes the symbols of the target to `path` as json (`format` 0) or toml (`format` 1).
+#[no_mangle]
+pub unsafe extern "C" fn symbol_save(target: TargetHandle, path_repr: ByteBuffer, format: u32) -> CallResult<bool> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            let path = path_repr.into_string();
+
+            match process.save_symbols(&path, format) {
+                Ok(_) => CallResult::new(Some(1), None),
+                Err(err) => {
+                    warn!("failed to save the symbols to {:?}: {:?}", path, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
+}
+
+/// Defines the symbols of a file written by `symbol_save`, evaluating their expressions against
+/// the target in the saved order. Symbols that no longer evaluate are skipped. Returns the number
+/// of defined symbols.
+#[no_mangle]
+pub unsafe extern "C" fn symbol_load(target: TargetHandle, path_repr: ByteBuffer, format: u32) -> CallResult<usize> {
+    match validate_target_handle(target) {
+        Ok(process) => {
+            let path = path_repr.into_string();
 
-    collected
+            match process.load_symbols(&path, format) {
+                Ok(count) => {
+                    info!("loaded {:?} symbols from {:?}", count, path);
+                    CallResult::new(Some(count), None)
+                },
+                Err(err) => {
+                    warn!("failed to load the symbols from {:?}: {:?}", path, err);
+                    CallResult::from(err.as_details())
+                }
+            }
+        },
+        Err(err) => {
+            err.into()
+        }
+    }
 }
 
 #[no_mangle] 
@@ -257,58 +1119,247 @@ pub extern "C" fn log_to_file(level: usize) {
     info!("log level has been set to level #{:?}", level)
 }
 
-fn parse_pattern(pattern_str: &str) -> Vec<Vec<u8>> {
-    pattern_str
-        .split_whitespace()
-        .map(|s| {
-            if s == "??" {
-                Vec::new()
-            } else {
-                let byte = u8::from_str_radix(&s, 16)
-                    .expect("could not parse byte pattern");
-                vec![byte]
-            }
-        })
-        .collect()
+/// Frees a `ByteBuffer` returned by the library. Strings and buffers its elements point to are
+/// not freed, release them with `free_string` / `free_buffer` first.
+#[no_mangle]
+pub unsafe extern "C" fn free_buffer(buffer: *mut ByteBuffer) {
+    if !buffer.is_null() {
+        Box::from_raw(buffer).free();
+    }
 }
 
-fn compare_func(window: &[u8], pattern: &[Vec<u8>]) -> bool {
-    window.iter().zip(pattern.iter()).all(|(byte, pat)| {
-        if pat.is_empty() {
-            true
-        } else {
-            pat.contains(byte)
-        }
-    })
+/// Frees a C string returned by the library, including `CallResult::error`.
+#[no_mangle]
+pub unsafe extern "C" fn free_string(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Frees the result of `write_memory_ex`.
+#[no_mangle]
+pub unsafe extern "C" fn free_write_result(result: *mut CWriteResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
 }
 
-fn find_all_occurrences(buff: &[u8], pattern: &[Vec<u8>]) -> Vec<usize> {
-    let mut occurrences = Vec::new();
-    let mut start = 0;
+/// Version of the exported functions and structs, see `rsmem.h`.
+#[no_mangle]
+pub extern "C" fn rsmem_abi_version() -> u32 {
+    abi::RSMEM_ABI_VERSION
+}
+
+/// Size of the `AbiStruct` with the given id in this build, 0 for unknown ids.
+#[no_mangle]
+pub extern "C" fn rsmem_struct_size(id: u32) -> usize {
+    abi::struct_size(id)
+}
 
-    while start + pattern.len() <= buff.len() {
-        if let Some(index) = buff[start..]
-            .windows(pattern.len())
-            .position(|window| compare_func(window, pattern))
-        {
-            occurrences.push(start + index);
-            start += index + 1;
-        } else {
-            break;
+/// Checks the ABI version and the struct sizes (`count` `usize`s ordered by `AbiStruct`) a host
+/// was built against, so layout mismatches surface at load time instead of as corrupted results.
+#[no_mangle]
+pub unsafe extern "C" fn rsmem_abi_handshake(version: u32, sizes: *const usize, count: usize) -> CallResult<bool> {
+    if version != abi::RSMEM_ABI_VERSION {
+        warn!("host was built against abi version {:?}, library is {:?}", version, abi::RSMEM_ABI_VERSION);
+        return CallResult::from(MemoryError::AbiVersionMismatch.as_details());
+    }
+
+    let sizes = match (sizes.is_null(), count) {
+        (_, 0) => &[][..],
+        (true, _) => return CallResult::from(MemoryError::AbiLayoutMismatch.as_details()),
+        (false, count) => std::slice::from_raw_parts(sizes, count),
+    };
+
+    match abi::find_mismatch(sizes) {
+        None => CallResult::new(Some(1), None),
+        Some(id) => {
+            warn!("host layout of struct #{:?} is {:?} bytes, library layout is {:?}", id, sizes.get(id), abi::struct_size(id as u32));
+            CallResult::from(MemoryError::AbiLayoutMismatch.as_details())
         }
     }
+}
 
-    occurrences
+/// The target attached through `attach` for the process, if any.
+fn find_target(pid: u32) -> Option<Process> {
+    TARGETS.lock()
+        .iter()
+        .flatten()
+        .find(|target| target.pid() == pid)
+        .map(|target| Process::from_target(target.clone()))
 }
 
-fn validate_target_handle(target_handle: TargetHandle) -> Result<Arc<ITarget>, rust_i18n::error::Error> {
-    match TARGETS.lock().get(target_handle) {
+/// Evaluates the address argument of the `*_expr` exports like `evaluate_expression`.
+unsafe fn resolve_address(target: TargetHandle, address_repr: ByteBuffer, pointer_size: usize) -> Result<usize, MemoryError> {
+    let process = validate_target_handle(target)
+        .map_err(|_| MemoryError::InvalidTargetHandle)?;
+    let expression = address_repr.into_string();
+
+    process.evaluate(&expression, pointer_size).map_err(|err| {
+        warn!("failed to evaluate {:?}: {:?}", expression, err);
+        err
+    })
+}
+
+fn validate_target_handle(target_handle: TargetHandle) -> Result<Process, rust_i18n::error::Error> {
+    match TARGETS.lock().get(target_handle).and_then(Option::as_ref) {
         None => {
             warn!("invalid target handle supplied: ({:?})", target_handle);
             Result::Err(MemoryError::InvalidTargetHandle.into())
         }
         Some(target) => {
-            Result::Ok(target.clone())
+            Result::Ok(Process::from_target(target.clone()))
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use udbg::pe::{PAGE_EXECUTE_READ, PAGE_READONLY, PAGE_READWRITE};
+
+    use super::*;
+
+    // targets are global, every test registers its own mock under a distinct pid
+    fn mock(pid: u32) -> (Arc<MockTarget>, TargetHandle) {
+        let target = Arc::new(MockTarget::new(pid));
+        let handle = insert_target(target.clone());
+        (target, handle)
+    }
+
+    unsafe fn pattern(source: &str) -> ByteBuffer {
+        ByteBuffer::from_vec(CString::new(source).unwrap().into_bytes_with_nul())
+    }
+
+    #[test]
+    fn aob_query_finds_matches_across_pages() {
+        let (target, handle) = mock(0x7001);
+        target
+            .map(0x1000, vec![0x90, 0x48, 0x8B, 0x05, 0x11, 0x22, 0x33, 0x44], PAGE_EXECUTE_READ)
+            .map(0x2000, vec![0x00, 0x48, 0x8B, 0x05, 0x00, 0x00, 0x00, 0x00], PAGE_READWRITE);
+
+        let matches = unsafe { aob_query(handle, pattern("48 8B 05 ?? ?? ?? ??"), PageFilter::default(), 1) }.unwrap();
+        assert_eq!(matches, vec![0x1001, 0x2001]);
+
+        let matches = unsafe { aob_query(handle, pattern("48 8B 05 ?? ?? ?? ??"), PageFilter { mapped: false, readable: false, writable: false, executable: true }, 1) }.unwrap();
+        assert_eq!(matches, vec![0x1001]);
+    }
+
+    #[test]
+    fn aob_query_rejects_invalid_patterns() {
+        let (_, handle) = mock(0x7002);
+        let result = unsafe { aob_query(handle, pattern("48 XY"), PageFilter::default(), 1) };
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn collect_pages_lists_mapped_pages() {
+        let (target, handle) = mock(0x7003);
+        target
+            .map(0x1000, vec![0u8; 0x100], PAGE_READONLY)
+            .map(0x3000, vec![0u8; 0x200], PAGE_READWRITE);
+
+        let pages = unsafe { collect_pages(handle) }.unwrap();
+        let ranges = pages.iter().map(|page| (page.base, page.size)).collect::<Vec<_>>();
+        assert_eq!(ranges, vec![(0x1000, 0x100), (0x3000, 0x200)]);
+    }
+
+    #[test]
+    fn read_bytes_stops_at_unmapped_memory() {
+        let (target, handle) = mock(0x7004);
+        target
+            .map(0x1000, vec![1, 2, 3, 4], PAGE_READONLY)
+            .map(0x1004, vec![5, 6], PAGE_READONLY);
+
+        let bytes = unsafe { read_bytes(handle, 0x1002, 8) }.unwrap();
+        assert_eq!(bytes, vec![3, 4, 5, 6]);
+
+        let bytes = unsafe { read_bytes(handle, 0x5000, 4) }.unwrap();
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn write_memory_spans_pages() {
+        let (target, handle) = mock(0x7005);
+        target
+            .map(0x1000, vec![0u8; 4], PAGE_READWRITE)
+            .map(0x1004, vec![0u8; 4], PAGE_READWRITE);
+
+        let written = unsafe { write_memory(handle, 0x1002, ByteBuffer::from_slice(&[1, 2, 3, 4])) };
+        assert!(!written.is_err());
+        assert_eq!(written.result, 4);
+        assert_eq!(unsafe { read_bytes(handle, 0x1000, 8) }.unwrap(), vec![0, 0, 1, 2, 3, 4, 0, 0]);
+    }
+
+    #[test]
+    fn write_memory_fails_on_read_only_pages() {
+        let (target, handle) = mock(0x7006);
+        target
+            .map(0x1000, vec![0u8; 4], PAGE_READWRITE)
+            .map(0x1004, vec![0u8; 4], PAGE_READONLY);
+
+        let written = unsafe { write_memory(handle, 0x1002, ByteBuffer::from_slice(&[1, 2, 3, 4])) };
+        assert_eq!(written.result, 0);
+        assert_eq!(unsafe { read_bytes(handle, 0x1000, 8) }.unwrap(), vec![0u8; 8]);
+    }
+
+    #[test]
+    fn free_memory_unmaps_the_given_range() {
+        let (target, handle) = mock(0x7007);
+        target.map(0x1000, vec![0u8; 0x3000], PAGE_READWRITE);
+
+        let freed = unsafe { free_memory(handle, 0x2000, 0x1000) };
+        assert!(!freed.is_err());
+
+        let pages = unsafe { collect_pages(handle) }.unwrap();
+        let ranges = pages.iter().map(|page| (page.base, page.size)).collect::<Vec<_>>();
+        assert_eq!(ranges, vec![(0x1000, 0x1000), (0x3000, 0x1000)]);
+    }
+
+    #[test]
+    fn protect_memory_changes_the_whole_range() {
+        let (target, handle) = mock(0x7009);
+        target
+            .map(0x1000, vec![0u8; 0x1000], PAGE_READWRITE)
+            .map(0x2000, vec![0u8; 0x2000], PAGE_READWRITE);
+
+        let old = unsafe { protect_memory(handle, 0x1800, 0x1000, PAGE_READONLY) };
+        assert_eq!(old.result, PAGE_READWRITE as usize);
+
+        let ranges = target.collect_memory_info()
+            .iter()
+            .map(|page| (page.base, page.size, page.protect))
+            .collect::<Vec<_>>();
+        assert_eq!(ranges, vec![
+            (0x1000, 0x800, PAGE_READWRITE),
+            (0x1800, 0x800, PAGE_READONLY),
+            (0x2000, 0x800, PAGE_READONLY),
+            (0x2800, 0x1800, PAGE_READWRITE),
+        ]);
+
+        assert_eq!(unsafe { write_memory(handle, 0x27FF, ByteBuffer::from_slice(&[1])) }.result, 0);
+        assert!(unsafe { protect_memory(handle, 0x3800, 0x1000, PAGE_READONLY) }.is_err());
+    }
+
+    #[test]
+    fn expression_exports_resolve_symbols() {
+        let (target, handle) = mock(0x7008);
+        target.map(0x1000, vec![0u8; 8], PAGE_READWRITE);
+
+        let registered = unsafe { symbol_register(handle, pattern("health"), pattern("1002"), 0) };
+        assert!(!registered.is_err());
+
+        let written = unsafe { write_memory_expr(handle, pattern("health+2"), ByteBuffer::from_slice(&[7, 8])) };
+        assert_eq!(written.result, 2);
+        assert_eq!(unsafe { read_bytes_expr(handle, pattern("health"), 4) }.unwrap(), vec![0, 0, 7, 8]);
+
+        assert!(unsafe { read_bytes_expr(handle, pattern("mana"), 4) }.is_err());
+    }
+
+    #[test]
+    fn invalid_handles_are_errors() {
+        let result = unsafe { read_bytes(usize::MAX, 0x1000, 4) };
+        assert!(result.is_err());
+    }
+}
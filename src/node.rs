@@ -0,0 +1,268 @@
+use napi::{bindgen_prelude::{Buffer, ToNapiValue}, Env, JsObject, Status};
+use napi_derive::napi;
+use rust_i18n::error::AsDetails;
+
+use crate::{
+    definitions::PageFilter,
+    error::MemoryError,
+    float::{check_decimals, FloatMatcher, FloatMode, FloatType},
+    pattern::resolve_alignment,
+    process::{Process, Region},
+    text::TextEncoding,
+};
+
+impl From<MemoryError> for napi::Error {
+    fn from(value: MemoryError) -> Self {
+        napi::Error::new(Status::GenericFailure, value.as_details().message.clone())
+    }
+}
+
+// user space addresses fit in the 53 bits of a js number
+fn to_usize(value: i64) -> napi::Result<usize> {
+    usize::try_from(value)
+        .map_err(|_| napi::Error::new(Status::InvalidArg, format!("{} is not a valid address or size", value)))
+}
+
+/// Settles the returned promise with the result of `scan`, run on the rayon pool so the event
+/// loop is never blocked.
+fn spawn_scan<T, F>(env: &Env, scan: F) -> napi::Result<JsObject>
+where
+    T: ToNapiValue + Send + 'static,
+    F: FnOnce() -> napi::Result<T> + Send + 'static,
+{
+    let (deferred, promise) = env.create_deferred()?;
+
+    rayon::spawn(move || match scan() {
+        Ok(value) => deferred.resolve(move |_| Ok(value)),
+        Err(err) => deferred.reject(err),
+    });
+
+    Ok(promise)
+}
+
+#[napi(object)]
+pub struct RegionInfo {
+    pub base: i64,
+    pub size: i64,
+    pub protection: u32,
+    pub usage: Option<String>,
+    pub readable: bool,
+    pub writable: bool,
+    pub executable: bool,
+}
+
+impl From<&Region> for RegionInfo {
+    fn from(value: &Region) -> Self {
+        RegionInfo {
+            base: value.base() as i64,
+            size: value.size() as i64,
+            protection: value.protection(),
+            usage: value.usage().map(|usage| usage.to_owned()),
+            readable: value.is_readable(),
+            writable: value.is_writable(),
+            executable: value.is_executable(),
+        }
+    }
+}
+
+/// Page filter and alignment of a scan, every kind of page is searched by default.
+#[napi(object)]
+#[derive(Default)]
+pub struct ScanOptions {
+    pub readable: Option<bool>,
+    pub writable: Option<bool>,
+    pub executable: Option<bool>,
+    pub mapped: Option<bool>,
+    pub alignment: Option<u32>,
+}
+
+impl ScanOptions {
+    fn filter(&self) -> PageFilter {
+        PageFilter {
+            mapped: self.mapped.unwrap_or(false),
+            readable: self.readable.unwrap_or(true),
+            writable: self.writable.unwrap_or(true),
+            executable: self.executable.unwrap_or(true),
+        }
+    }
+
+    fn alignment(&self) -> usize {
+        self.alignment.unwrap_or(0) as usize
+    }
+}
+
+#[napi(object)]
+pub struct StringMatch {
+    pub address: i64,
+    /// "ascii", "utf8" or "utf16le"
+    pub encoding: String,
+}
+
+#[napi(object)]
+pub struct RegexMatch {
+    pub address: i64,
+    pub length: i64,
+}
+
+#[napi(object)]
+pub struct FloatMatch {
+    pub address: i64,
+    pub value: f64,
+}
+
+#[napi(object)]
+pub struct FloatQuery {
+    pub value: f64,
+    pub double: Option<bool>,
+    /// "rounded" (default) or "truncated" to `decimals` (at most 15), or "epsilon"
+    pub mode: Option<String>,
+    pub decimals: Option<u32>,
+    pub epsilon: Option<f64>,
+    /// Re-checks these addresses instead of scanning the regions.
+    pub candidates: Option<Vec<i64>>,
+}
+
+impl FloatQuery {
+    fn matcher(&self, alignment: usize) -> Result<FloatMatcher, MemoryError> {
+        let value_type = match self.double.unwrap_or(false) {
+            true => FloatType::F64,
+            false => FloatType::F32,
+        };
+
+        let decimals = self.decimals.unwrap_or(0);
+        let mode = match self.mode.as_deref().unwrap_or("rounded") {
+            "rounded" => FloatMode::Rounded { decimals: check_decimals(decimals)? },
+            "truncated" => FloatMode::Truncated { decimals: check_decimals(decimals)? },
+            "epsilon" => match self.epsilon {
+                Some(epsilon) if epsilon >= 0.0 => FloatMode::Epsilon { epsilon },
+                _ => return Err(MemoryError::InvalidScanParameters),
+            },
+            _ => return Err(MemoryError::InvalidScanParameters),
+        };
+
+        let alignment = resolve_alignment(alignment, value_type.size())?;
+        Ok(FloatMatcher { value_type, mode, value: self.value, alignment })
+    }
+}
+
+/// An attached process. Scans return promises, reads and writes are synchronous.
+#[napi(js_name = "Process")]
+pub struct NodeProcess {
+    inner: Process,
+}
+
+#[napi]
+impl NodeProcess {
+    #[napi(factory)]
+    pub fn attach(pid: u32) -> napi::Result<Self> {
+        Ok(NodeProcess { inner: Process::attach(pid)? })
+    }
+
+    #[napi(getter)]
+    pub fn pid(&self) -> u32 {
+        self.inner.pid()
+    }
+
+    /// Restores the patches of the process and detaches from it.
+    #[napi]
+    pub fn detach(&self) {
+        self.inner.detach();
+    }
+
+    #[napi]
+    pub fn regions(&self) -> Vec<RegionInfo> {
+        self.inner.regions()
+            .iter()
+            .map(RegionInfo::from)
+            .collect()
+    }
+
+    /// Reads up to `size` bytes, the result is shorter when the range is partially unreadable.
+    #[napi]
+    pub fn read(&self, address: i64, size: u32) -> napi::Result<Buffer> {
+        Ok(self.inner.read(to_usize(address)?, size as usize).into())
+    }
+
+    /// Returns the number of written bytes, `force` writes to read-only and executable pages too.
+    #[napi]
+    pub fn write(&self, address: i64, data: Buffer, force: Option<bool>) -> napi::Result<i64> {
+        let (written, _) = self.inner.write_with(to_usize(address)?, &data, force.unwrap_or(false))?;
+        Ok(written as i64)
+    }
+
+    #[napi(ts_return_type = "Promise<number[]>")]
+    pub fn aob(&self, env: Env, pattern: String, options: Option<ScanOptions>) -> napi::Result<JsObject> {
+        let process = self.inner.clone();
+        let options = options.unwrap_or_default();
+
+        spawn_scan(&env, move || {
+            let matches = process.scanner().filter(options.filter()).alignment(options.alignment()).aob(&pattern)?;
+            Ok(matches.into_iter().map(|address| address as i64).collect::<Vec<_>>())
+        })
+    }
+
+    /// `encodings` flags 1 ascii, 2 utf-8 and 4 utf-16le, all of them by default.
+    #[napi(ts_return_type = "Promise<StringMatch[]>")]
+    pub fn string_scan(&self, env: Env, text: String, encodings: Option<u32>, case_insensitive: Option<bool>, options: Option<ScanOptions>) -> napi::Result<JsObject> {
+        let process = self.inner.clone();
+        let options = options.unwrap_or_default();
+
+        spawn_scan(&env, move || {
+            let matches = process.scanner()
+                .filter(options.filter())
+                .alignment(options.alignment())
+                .string(&text, encodings.unwrap_or(7), case_insensitive.unwrap_or(false))?;
+
+            Ok(matches.into_iter()
+                .map(|(address, encoding)| StringMatch {
+                    address: address as i64,
+                    encoding: match encoding {
+                        TextEncoding::Ascii => "ascii",
+                        TextEncoding::Utf8 => "utf8",
+                        TextEncoding::Utf16Le => "utf16le",
+                    }.to_owned(),
+                })
+                .collect::<Vec<_>>())
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<RegexMatch[]>")]
+    pub fn regex_scan(&self, env: Env, pattern: String, max_match_len: Option<u32>, max_matches: Option<u32>, options: Option<ScanOptions>) -> napi::Result<JsObject> {
+        let process = self.inner.clone();
+        let options = options.unwrap_or_default();
+
+        spawn_scan(&env, move || {
+            let matches = process.scanner()
+                .filter(options.filter())
+                .alignment(options.alignment())
+                .regex(&pattern, max_match_len.unwrap_or(0) as usize, max_matches.unwrap_or(0) as usize)?;
+
+            Ok(matches.into_iter()
+                .map(|(address, length)| RegexMatch { address: address as i64, length: length as i64 })
+                .collect::<Vec<_>>())
+        })
+    }
+
+    #[napi(ts_return_type = "Promise<FloatMatch[]>")]
+    pub fn float_scan(&self, env: Env, query: FloatQuery, options: Option<ScanOptions>) -> napi::Result<JsObject> {
+        let process = self.inner.clone();
+        let options = options.unwrap_or_default();
+
+        spawn_scan(&env, move || {
+            let matcher = query.matcher(options.alignment())?;
+            let candidates = query.candidates
+                .map(|candidates| candidates.into_iter().map(to_usize).collect::<napi::Result<Vec<_>>>())
+                .transpose()?;
+
+            let scanner = process.scanner().filter(options.filter());
+            let matches = match candidates {
+                Some(candidates) => scanner.float_candidates(&matcher, &candidates),
+                None => scanner.float(&matcher),
+            };
+
+            Ok(matches.into_iter()
+                .map(|(address, value)| FloatMatch { address: address as i64, value })
+                .collect::<Vec<_>>())
+        })
+    }
+}
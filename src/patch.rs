@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, HashMap};
+
+use lazy_static::lazy_static;
+use log::{info, warn};
+use once_cell::sync::Lazy;
+
+use crate::{definitions::{ArcM, ByteBuffer, IMemoryTarget}, error::MemoryError};
+
+pub type PatchId = usize;
+
+lazy_static! {
+    // patch registries of attached targets, keyed by pid
+    static ref PATCHES: Lazy<ArcM<HashMap<u32, PatchRegistry>>> = Lazy::new(|| ArcM::default());
+}
+
+#[derive(Debug, Clone)]
+pub struct Patch {
+    pub id: PatchId,
+    pub address: usize,
+    pub original: Vec<u8>,
+    pub patched: Vec<u8>,
+}
+
+impl Patch {
+    fn overlaps(&self, address: usize, size: usize) -> bool {
+        self.address < address + size && address < self.address + self.patched.len()
+    }
+}
+
+#[derive(Default)]
+pub struct PatchRegistry {
+    next_id: PatchId,
+    patches: BTreeMap<PatchId, Patch>,
+}
+
+impl PatchRegistry {
+    /// Saves the bytes at `address` and overwrites them with `bytes`, whatever the protection of
+    /// the pages. When `expected` is not empty the current bytes have to match it, otherwise the
+    /// patch is refused. An empty `expected` skips the check.
+    pub fn apply(&mut self, target: &IMemoryTarget, address: usize, bytes: &[u8], expected: &[u8]) -> Result<PatchId, MemoryError> {
+        if self.patches.values().any(|patch| patch.overlaps(address, bytes.len())) {
+            return Err(MemoryError::PatchOverlaps);
+        }
+
+        let original = target.read_bytes(address, bytes.len());
+        if original.len() != bytes.len() {
+            return Err(MemoryError::FailedtoReadProcessMemory);
+        }
+
+        if !expected.is_empty() && original != expected {
+            return Err(MemoryError::PatchOriginalMismatch);
+        }
+
+        // patches usually target code, which is not writable
+        match target.write_memory_forced(address, bytes) {
+            Some((written, _)) if written == bytes.len() => {},
+            _ => return Err(MemoryError::FailedToWriteProcessMemory),
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.patches.insert(id, Patch { id, address, original, patched: bytes.to_vec() });
+
+        Ok(id)
+    }
+
+    /// Writes the saved bytes back, provided the patched bytes are still in place.
+    pub fn restore(&mut self, target: &IMemoryTarget, id: PatchId) -> Result<(), MemoryError> {
+        let patch = self.patches.get(&id)
+            .ok_or(MemoryError::PatchNotFound)?;
+
+        if target.read_bytes(patch.address, patch.patched.len()) != patch.patched {
+            return Err(MemoryError::PatchOriginalMismatch);
+        }
+
+        match target.write_memory_forced(patch.address, &patch.original) {
+            Some((written, _)) if written == patch.original.len() => {},
+            _ => return Err(MemoryError::FailedToWriteProcessMemory),
+        }
+
+        self.patches.remove(&id);
+        Ok(())
+    }
+
+    /// Restores every patch, newest first. Returns the number of restored patches.
+    pub fn restore_all(&mut self, target: &IMemoryTarget) -> usize {
+        let ids = self.patches.keys().rev().cloned().collect::<Vec<_>>();
+
+        ids.into_iter()
+            .filter(|id| match self.restore(target, *id) {
+                Ok(_) => true,
+                Err(err) => {
+                    warn!("failed to restore the patch #{:?} of the process #{:?}: {:?}", id, target.pid(), err);
+                    false
+                }
+            })
+            .count()
+    }
+
+    pub fn patches(&self) -> impl Iterator<Item = &Patch> {
+        self.patches.values()
+    }
+}
+
+pub fn with_registry<R>(target: &IMemoryTarget, f: impl FnOnce(&mut PatchRegistry) -> R) -> R {
+    let mut registries = PATCHES.lock();
+    f(registries.entry(target.pid()).or_default())
+}
+
+/// Restores and forgets every patch of the target, called on detach.
+pub fn release(target: &IMemoryTarget) {
+    let registry = PATCHES.lock().remove(&target.pid());
+
+    if let Some(mut registry) = registry {
+        let count = registry.restore_all(target);
+        info!("restored {:?} patches of the process #{:?}", count, target.pid());
+    }
+}
+
+// Patch info
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CPatchInfo {
+    pub id: PatchId,
+    pub address: usize,
+    pub size: usize,
+    pub original: *mut ByteBuffer,
+    pub patched: *mut ByteBuffer,
+}
+
+impl From<&Patch> for CPatchInfo {
+    fn from(value: &Patch) -> Self {
+        unsafe {
+            CPatchInfo {
+                id: value.id,
+                address: value.address,
+                size: value.patched.len(),
+                original: ByteBuffer::from_slice(&value.original).into_raw(),
+                patched: ByteBuffer::from_slice(&value.patched).into_raw(),
+            }
+        }
+    }
+}
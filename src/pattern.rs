@@ -0,0 +1,223 @@
+use std::ffi::c_char;
+
+use crate::{definitions::ByteBuffer, error::MemoryError, helpers::like::CStringLike};
+
+/// Bytes enclosed in `( .. )` or `(name: .. )` within a pattern.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub name: Option<String>,
+    pub offset: usize,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Pattern {
+    // an empty entry matches any byte
+    pub bytes: Vec<Vec<u8>>,
+    pub groups: Vec<Group>,
+}
+
+/// Parses space separated hex bytes, `??` wildcards and `(`/`)` delimited groups such as
+/// `48 8B 05 (?? ?? ?? ??)`. Groups can be named, `8B 88 (offset: ?? ?? ?? ??)`.
+pub fn parse_pattern(pattern_str: &str) -> Result<Pattern, MemoryError> {
+    let mut pattern = Pattern::default();
+    let mut open: Option<(Option<String>, usize)> = None;
+
+    for token in pattern_str.split_whitespace() {
+        let mut token = token;
+
+        if let Some(rest) = token.strip_prefix('(') {
+            if open.is_some() {
+                return Err(MemoryError::InvalidPattern);
+            }
+
+            token = rest;
+            let name = match token.split_once(':') {
+                Some((name, rest)) => {
+                    token = rest;
+                    Some(name.to_owned())
+                },
+                None => None,
+            };
+
+            if name.as_ref().is_some_and(|name| name.is_empty() || pattern.groups.iter().any(|group| group.name.as_ref() == Some(name))) {
+                return Err(MemoryError::InvalidPattern);
+            }
+
+            open = Some((name, pattern.bytes.len()));
+        }
+
+        let closes = token.ends_with(')');
+        token = token.trim_end_matches(')');
+
+        match token {
+            "" => {},
+            "??" | "?" => pattern.bytes.push(Vec::new()),
+            _ => {
+                let byte = u8::from_str_radix(token, 16)
+                    .map_err(|_| MemoryError::InvalidPattern)?;
+                pattern.bytes.push(vec![byte]);
+            }
+        }
+
+        if closes {
+            let (name, offset) = open.take().ok_or(MemoryError::InvalidPattern)?;
+            if offset == pattern.bytes.len() {
+                return Err(MemoryError::InvalidPattern);
+            }
+
+            pattern.groups.push(Group { name, offset, len: pattern.bytes.len() - offset });
+        }
+    }
+
+    if open.is_some() || pattern.bytes.is_empty() {
+        return Err(MemoryError::InvalidPattern);
+    }
+
+    Ok(pattern)
+}
+
+pub fn compare_func(window: &[u8], pattern: &[Vec<u8>]) -> bool {
+    window.iter().zip(pattern.iter()).all(|(byte, pat)| {
+        if pat.is_empty() {
+            true
+        } else {
+            pat.contains(byte)
+        }
+    })
+}
+
+pub fn find_all_occurrences(buff: &[u8], pattern: &[Vec<u8>]) -> Vec<usize> {
+    let mut occurrences = Vec::new();
+    let mut start = 0;
+
+    while start + pattern.len() <= buff.len() {
+        if let Some(index) = buff[start..]
+            .windows(pattern.len())
+            .position(|window| compare_func(window, pattern))
+        {
+            occurrences.push(start + index);
+            start += index + 1;
+        } else {
+            break;
+        }
+    }
+
+    occurrences
+}
+
+/// Checks a requested scan alignment, 0 picks the natural alignment of the scanned type.
+pub fn resolve_alignment(alignment: usize, natural: usize) -> Result<usize, MemoryError> {
+    match alignment {
+        0 => Ok(natural),
+        1 | 2 | 4 | 8 | 16 => Ok(alignment),
+        _ => Err(MemoryError::InvalidScanParameters),
+    }
+}
+
+/// `find_all_occurrences` that only tests offsets whose address, `base + offset`, is a multiple
+/// of `alignment`.
+pub fn find_aligned_occurrences(buff: &[u8], pattern: &[Vec<u8>], base: usize, alignment: usize) -> Vec<usize> {
+    if alignment <= 1 {
+        return find_all_occurrences(buff, pattern);
+    }
+
+    if pattern.len() > buff.len() {
+        return Vec::new();
+    }
+
+    let first = (alignment - base % alignment) % alignment;
+    (first..=buff.len() - pattern.len())
+        .step_by(alignment)
+        .filter(|offset| compare_func(&buff[*offset..*offset + pattern.len()], pattern))
+        .collect()
+}
+
+/// Decodes the captured bytes as a little-endian integer of their width, groups wider than
+/// 8 bytes are not decodable.
+pub fn decode_capture(bytes: &[u8]) -> Option<u64> {
+    if bytes.is_empty() || bytes.len() > 8 {
+        return None;
+    }
+
+    let mut value = [0u8; 8];
+    value[..bytes.len()].copy_from_slice(bytes);
+    Some(u64::from_le_bytes(value))
+}
+
+/// The bytes one group of a pattern matched.
+#[derive(Debug, Clone)]
+pub struct Capture {
+    pub name: Option<String>,
+    pub offset: usize,
+    pub size: usize,
+    /// `None` when the group could not be decoded
+    pub value: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CaptureMatch {
+    pub address: usize,
+    pub captures: Vec<Capture>,
+}
+
+// Captures
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CCapture {
+    /// null for unnamed groups
+    pub name: *const c_char,
+    pub offset: usize,
+    pub size: usize,
+    /// zero extended little-endian value, only meaningful when `resolved` is set
+    pub value: u64,
+    /// false when the group could not be decoded, `value` is 0 then
+    pub resolved: bool,
+}
+
+#[repr(C)]
+pub struct CCaptureMatch {
+    pub address: usize,
+    /// `CCapture`s in the order the groups appear in the pattern
+    pub captures: *mut ByteBuffer,
+}
+
+impl From<&Capture> for CCapture {
+    fn from(value: &Capture) -> Self {
+        CCapture {
+            name: match value.name.as_ref() {
+                Some(name) => name.as_c_char_ptr(),
+                None => std::ptr::null(),
+            },
+            offset: value.offset,
+            size: value.size,
+            value: value.value.unwrap_or_default(),
+            resolved: value.value.is_some(),
+        }
+    }
+}
+
+impl From<&CaptureMatch> for CCaptureMatch {
+    fn from(value: &CaptureMatch) -> Self {
+        let captures = value.captures.iter()
+            .map(|capture| CCapture::from(capture))
+            .collect::<Vec<_>>();
+
+        CCaptureMatch {
+            address: value.address,
+            captures: unsafe { ByteBuffer::from_sized_vec(captures).into_raw() },
+        }
+    }
+}
+
+// Resolved match
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CResolvedMatch {
+    pub address: usize,
+    /// only meaningful when `resolved` is set
+    pub target: usize,
+    /// false when the group could not be resolved, e.g. it is not an operand of a decodable
+    /// instruction, `target` is 0 then
+    pub resolved: bool,
+}
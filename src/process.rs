@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+use once_cell::sync::Lazy;
+use udbg::{memory::MemoryPage, pe::*};
+
+use crate::{
+    asm,
+    definitions::{EngineHandleArc, IMemoryTarget, ITargetHandle, PageFilter, WriteMechanism},
+    disasm::{self, Instruction},
+    error::MemoryError,
+    expr,
+    guess::{self, Slot},
+    layout,
+    patch::{self, Patch, PatchId},
+    scanner::Scanner,
+    signature,
+    symbol::{self, Symbol},
+};
+
+lazy_static! {
+    static ref ENGINE: Lazy<EngineHandleArc> = Lazy::new(|| EngineHandleArc::default());
+}
+
+/// An opened process, or any other `MemoryTarget`. Cloning is cheap, clones share the target.
+#[derive(Clone)]
+pub struct Process {
+    target: Arc<IMemoryTarget>,
+}
+
+impl Process {
+    pub fn attach(pid: u32) -> Result<Self, MemoryError> {
+        let target = ENGINE.lock().open(pid)
+            .map_err(|_| MemoryError::FailedToAttachProcess)?;
+
+        Ok(Self::from_target(Arc::new(ITargetHandle::new(target))))
+    }
+
+    pub fn from_target(target: Arc<IMemoryTarget>) -> Self {
+        Self { target }
+    }
+
+    pub fn target(&self) -> &Arc<IMemoryTarget> {
+        &self.target
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.target.pid()
+    }
+
+    pub fn regions(&self) -> Vec<Region> {
+        self.target.collect_memory_info()
+            .into_iter()
+            .map(Region::from)
+            .collect()
+    }
+
+    /// Lowest base of the pages mapped from the module file `name`, compared case-insensitively.
+    pub fn module_base(&self, name: &str) -> Option<usize> {
+        find_module_base(&self.regions(), name)
+    }
+
+    pub fn scanner(&self) -> Scanner<'_> {
+        Scanner::new(self)
+    }
+
+    /// Reads up to `size` bytes, stopping at the first unreadable byte.
+    pub fn read(&self, address: usize, size: usize) -> Vec<u8> {
+        self.target.read_bytes(address, size)
+    }
+
+    pub fn read_exact(&self, address: usize, size: usize) -> Result<Vec<u8>, MemoryError> {
+        let buffer = self.read(address, size);
+        match buffer.len() == size {
+            true => Ok(buffer),
+            false => Err(MemoryError::FailedtoReadProcessMemory),
+        }
+    }
+
+    pub fn write(&self, address: usize, bytes: &[u8]) -> Result<usize, MemoryError> {
+        match self.target.write_memory(address, bytes) {
+            Some(written) if written > 0 => Ok(written),
+            _ => Err(MemoryError::FailedToWriteProcessMemory),
+        }
+    }
+
+    /// Writes to read-only and executable pages too, see `MemoryTarget::write_memory_forced`.
+    pub fn write_forced(&self, address: usize, bytes: &[u8]) -> Result<(usize, WriteMechanism), MemoryError> {
+        match self.target.write_memory_forced(address, bytes) {
+            Some((written, mechanism)) if written > 0 => Ok((written, mechanism)),
+            _ => Err(MemoryError::FailedToWriteProcessMemory),
+        }
+    }
+
+    pub fn write_with(&self, address: usize, bytes: &[u8], force: bool) -> Result<(usize, WriteMechanism), MemoryError> {
+        match force {
+            true => self.write_forced(address, bytes),
+            false => self.write(address, bytes).map(|written| (written, WriteMechanism::Direct)),
+        }
+    }
+
+    pub fn allocate(&self, size: usize, protection: u32, near_address: usize) -> Result<usize, MemoryError> {
+        self.target.allocate_memory(size, protection, near_address)
+            .ok_or(MemoryError::FailedToAllocateMemory)
+    }
+
+    pub fn free(&self, address: usize, size: usize) -> Result<(), MemoryError> {
+        match self.target.free_memory(address, size) {
+            true => Ok(()),
+            false => Err(MemoryError::FailedToFreeMemory),
+        }
+    }
+
+    /// Returns the previous protection of the first page of the range.
+    pub fn protect(&self, address: usize, size: usize, protection: u32) -> Result<u32, MemoryError> {
+        self.target.protect_memory(address, size, protection)
+            .ok_or(MemoryError::FailedToProtectMemory)
+    }
+
+    pub fn apply_patch(&self, address: usize, bytes: &[u8], expected: &[u8]) -> Result<PatchId, MemoryError> {
+        patch::with_registry(self.target.as_ref(), |registry| registry.apply(self.target.as_ref(), address, bytes, expected))
+    }
+
+    pub fn restore_patch(&self, id: PatchId) -> Result<(), MemoryError> {
+        patch::with_registry(self.target.as_ref(), |registry| registry.restore(self.target.as_ref(), id))
+    }
+
+    pub fn restore_all_patches(&self) -> usize {
+        patch::with_registry(self.target.as_ref(), |registry| registry.restore_all(self.target.as_ref()))
+    }
+
+    pub fn patches(&self) -> Vec<Patch> {
+        patch::with_registry(self.target.as_ref(), |registry| registry.patches().cloned().collect())
+    }
+
+    pub fn disassemble(&self, address: usize, count: usize, bitness: u32) -> Vec<Instruction> {
+        disasm::disassemble(self.target.as_ref(), address, count, bitness)
+    }
+
+    /// Assembles `source` for `address`, padded with NOPs up to `length` when it is not 0, and
+    /// writes it.
+    pub fn assemble_and_write(&self, address: usize, source: &str, length: usize, bitness: u32, force: bool) -> Result<(usize, WriteMechanism), MemoryError> {
+        let bytes = asm::assemble(source, address, length, bitness)?;
+        self.write_with(address, &bytes, force)
+    }
+
+    pub fn make_signature(&self, address: usize, max_len: usize, bitness: u32) -> Result<String, MemoryError> {
+        signature::make_signature(self.target.as_ref(), address, max_len, bitness)
+    }
+
+    pub fn read_struct(&self, address: usize, layout_id: &str, max_depth: usize) -> Result<serde_json::Value, MemoryError> {
+        layout::read_struct(self.target.as_ref(), address, layout_id, max_depth)
+    }
+
+    pub fn guess_struct(&self, address: usize, size: usize, pointer_size: usize) -> Result<Vec<Slot>, MemoryError> {
+        guess::guess_struct(self.target.as_ref(), address, size, pointer_size)
+    }
+
+    /// Evaluates an address expression like `[[game.so+1F00]+18]+40`, see `Expression`.
+    /// `pointer_size` is 4 or 8, 0 picks the native size.
+    pub fn evaluate(&self, expression: &str, pointer_size: usize) -> Result<usize, MemoryError> {
+        expr::evaluate(self, expression, pointer_size)
+    }
+
+    /// Names an address so expressions and tables can refer to it.
+    pub fn register_symbol(&self, name: &str, address: usize) -> Result<(), MemoryError> {
+        let expression = format!("0x{:X}", address);
+        symbol::register(self.target.as_ref(), Symbol { name: name.to_owned(), address, expression, pointer_size: 0 })
+    }
+
+    /// Names the address `expression` evaluates to. Returns the address.
+    pub fn define_symbol(&self, name: &str, expression: &str, pointer_size: usize) -> Result<usize, MemoryError> {
+        symbol::define(self, name, expression, pointer_size)
+    }
+
+    pub fn symbol(&self, name: &str) -> Option<usize> {
+        symbol::lookup(self.target.as_ref(), name)
+    }
+
+    pub fn symbols(&self) -> Vec<Symbol> {
+        symbol::list(self.target.as_ref())
+    }
+
+    pub fn remove_symbol(&self, name: &str) -> bool {
+        symbol::remove(self.target.as_ref(), name)
+    }
+
+    pub fn save_symbols(&self, path: &str, format: u32) -> Result<(), MemoryError> {
+        symbol::save(self.target.as_ref(), path, format)
+    }
+
+    /// Defines the symbols of a file written by `save_symbols`. Returns how many were defined.
+    pub fn load_symbols(&self, path: &str, format: u32) -> Result<usize, MemoryError> {
+        symbol::load(self, path, format)
+    }
+
+    /// Restores the patches of the target, forgets its symbols and detaches from it.
+    pub fn detach(&self) {
+        patch::release(self.target.as_ref());
+        symbol::release(self.target.as_ref());
+        self.target.detach();
+    }
+}
+
+/// A page of the target's memory map.
+#[derive(Clone)]
+pub struct Region {
+    page: MemoryPage,
+}
+
+impl Region {
+    pub fn page(&self) -> &MemoryPage {
+        &self.page
+    }
+
+    pub fn base(&self) -> usize {
+        self.page.base
+    }
+
+    pub fn size(&self) -> usize {
+        self.page.size
+    }
+
+    pub fn end(&self) -> usize {
+        self.page.base + self.page.size
+    }
+
+    pub fn contains(&self, address: usize) -> bool {
+        self.base() <= address && address < self.end()
+    }
+
+    /// `PAGE_*` protection flags.
+    pub fn protection(&self) -> u32 {
+        self.page.protect
+    }
+
+    /// The module or mapping the page belongs to, when known.
+    pub fn usage(&self) -> Option<&str> {
+        self.page.info.as_deref()
+    }
+
+    /// File name of `usage`, e.g. `game.so` for `/opt/game/game.so`.
+    pub fn module_name(&self) -> Option<&str> {
+        self.usage()
+            .and_then(|usage| usage.rsplit(['/', '\\']).next())
+    }
+
+    pub fn is_module(&self, name: &str) -> bool {
+        self.module_name()
+            .is_some_and(|file| file.eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_mapped(&self) -> bool {
+        self.page.type_ == MEM_MAPPED
+    }
+
+    pub fn is_readable(&self) -> bool {
+        (self.page.protect & PAGE_READONLY) > 0
+    }
+
+    pub fn is_writable(&self) -> bool {
+        (self.page.protect & (PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY)) > 0
+    }
+
+    pub fn is_executable(&self) -> bool {
+        (self.page.protect & (PAGE_EXECUTE | PAGE_EXECUTE_READ | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY)) > 0
+    }
+
+    /// Committed, accessible private or image memory, the pages scans look at.
+    pub fn is_scannable(&self) -> bool {
+        self.page.state == MEM_COMMIT
+            && self.page.base < 140737488355327usize // todo use address space, eg: sysinfo in win32
+            && (self.page.protect & PAGE_GUARD) == 0
+            && (self.page.protect & PAGE_NOACCESS) == 0
+            && (self.page.type_ == MEM_PRIVATE || self.page.type_ == MEM_IMAGE)
+    }
+
+    pub fn matches(&self, filter: &PageFilter) -> bool {
+        if !self.is_scannable() || filter.mapped && !self.is_mapped() {
+            return false;
+        }
+
+        self.is_readable() && filter.readable
+            || self.is_writable() && filter.writable
+            || self.is_executable() && filter.executable
+    }
+}
+
+pub(crate) fn find_module_base(regions: &[Region], name: &str) -> Option<usize> {
+    regions.iter()
+        .filter(|region| region.is_module(name))
+        .map(Region::base)
+        .min()
+}
+
+impl From<MemoryPage> for Region {
+    fn from(page: MemoryPage) -> Self {
+        Self { page }
+    }
+}
@@ -0,0 +1,272 @@
+use pyo3::{create_exception, exceptions::PyException, prelude::*, types::PyBytes};
+use rust_i18n::error::AsDetails;
+
+use crate::{
+    definitions::PageFilter,
+    error::MemoryError,
+    float::{check_decimals, FloatMatcher, FloatMode, FloatType},
+    pattern::resolve_alignment,
+    process::{Process, Region},
+    text::TextEncoding,
+};
+
+create_exception!(rsmem, RsMemError, PyException, "Base class of every rsmem error.");
+create_exception!(rsmem, ProcessError, RsMemError, "The process could not be attached or is not attached.");
+create_exception!(rsmem, AccessError, RsMemError, "Reading, writing or changing the target's memory failed.");
+create_exception!(rsmem, PatternError, RsMemError, "A pattern, regex or scan parameter is invalid.");
+create_exception!(rsmem, PatchError, RsMemError, "A patch could not be applied or restored.");
+create_exception!(rsmem, AssemblyError, RsMemError, "Instructions could not be assembled.");
+create_exception!(rsmem, LayoutError, RsMemError, "A layout is invalid or not registered.");
+
+impl From<MemoryError> for PyErr {
+    fn from(value: MemoryError) -> Self {
+        let message = value.as_details().message.clone();
+
+        match value {
+            MemoryError::ProcessNotAttached
+            | MemoryError::ProcessAlreadyAttached
+            | MemoryError::FailedToAttachProcess
+            | MemoryError::InvalidTargetHandle => ProcessError::new_err(message),
+            MemoryError::FailedToWriteProcessMemory
+            | MemoryError::FailedtoReadProcessMemory
+            | MemoryError::FailedToAllocateMemory
+            | MemoryError::FailedToFreeMemory
+            | MemoryError::FailedToProtectMemory => AccessError::new_err(message),
+            MemoryError::PatchNotFound
+            | MemoryError::PatchOverlaps
+            | MemoryError::PatchOriginalMismatch => PatchError::new_err(message),
+            MemoryError::InvalidInstruction
+            | MemoryError::AmbiguousOperandSize
+            | MemoryError::AssembledCodeTooLong => AssemblyError::new_err(message),
+            MemoryError::SignatureNotUnique
+            | MemoryError::InvalidPattern
+            | MemoryError::PatternMissingGroup
+            | MemoryError::CaptureGroupTooWide
+            | MemoryError::InvalidScanParameters => PatternError::new_err(message),
+            MemoryError::InvalidLayout
+            | MemoryError::LayoutNotFound => LayoutError::new_err(message),
+            _ => RsMemError::new_err(message),
+        }
+    }
+}
+
+#[pyclass(name = "Region", module = "rsmem", frozen, get_all)]
+#[derive(Clone)]
+pub struct PyRegion {
+    base: usize,
+    size: usize,
+    protection: u32,
+    usage: Option<String>,
+    readable: bool,
+    writable: bool,
+    executable: bool,
+}
+
+#[pymethods]
+impl PyRegion {
+    fn __repr__(&self) -> String {
+        format!("Region(base={:#x}, size={:#x}, protection={:#x}, usage={:?})", self.base, self.size, self.protection, self.usage)
+    }
+}
+
+impl From<&Region> for PyRegion {
+    fn from(value: &Region) -> Self {
+        PyRegion {
+            base: value.base(),
+            size: value.size(),
+            protection: value.protection(),
+            usage: value.usage().map(|usage| usage.to_owned()),
+            readable: value.is_readable(),
+            writable: value.is_writable(),
+            executable: value.is_executable(),
+        }
+    }
+}
+
+#[pyclass(name = "StringMatch", module = "rsmem", frozen, get_all)]
+pub struct PyStringMatch {
+    address: usize,
+    /// "ascii", "utf8" or "utf16le"
+    encoding: &'static str,
+}
+
+#[pymethods]
+impl PyStringMatch {
+    fn __repr__(&self) -> String {
+        format!("StringMatch(address={:#x}, encoding={:?})", self.address, self.encoding)
+    }
+}
+
+#[pyclass(name = "RegexMatch", module = "rsmem", frozen, get_all)]
+pub struct PyRegexMatch {
+    address: usize,
+    length: usize,
+}
+
+#[pymethods]
+impl PyRegexMatch {
+    fn __repr__(&self) -> String {
+        format!("RegexMatch(address={:#x}, length={})", self.address, self.length)
+    }
+}
+
+#[pyclass(name = "FloatMatch", module = "rsmem", frozen, get_all)]
+pub struct PyFloatMatch {
+    address: usize,
+    value: f64,
+}
+
+#[pymethods]
+impl PyFloatMatch {
+    fn __repr__(&self) -> String {
+        format!("FloatMatch(address={:#x}, value={})", self.address, self.value)
+    }
+}
+
+/// An attached process, usable as a context manager that detaches on exit.
+#[pyclass(name = "Process", module = "rsmem")]
+pub struct PyProcess {
+    inner: Process,
+}
+
+#[pymethods]
+impl PyProcess {
+    #[staticmethod]
+    fn attach(pid: u32) -> PyResult<Self> {
+        Ok(PyProcess { inner: Process::attach(pid)? })
+    }
+
+    #[getter]
+    fn pid(&self) -> u32 {
+        self.inner.pid()
+    }
+
+    fn detach(&self) {
+        self.inner.detach();
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(&self, _kind: PyObject, _value: PyObject, _traceback: PyObject) -> bool {
+        self.inner.detach();
+        false
+    }
+
+    fn regions(&self) -> Vec<PyRegion> {
+        self.inner.regions()
+            .iter()
+            .map(PyRegion::from)
+            .collect()
+    }
+
+    /// Reads up to `size` bytes, the result is shorter when the range is partially unreadable.
+    fn read<'py>(&self, py: Python<'py>, address: usize, size: usize) -> Bound<'py, PyBytes> {
+        let bytes = py.allow_threads(|| self.inner.read(address, size));
+        PyBytes::new(py, &bytes)
+    }
+
+    #[pyo3(signature = (address, data, force = false))]
+    fn write(&self, py: Python<'_>, address: usize, data: &[u8], force: bool) -> PyResult<usize> {
+        let (written, _) = py.allow_threads(|| self.inner.write_with(address, data, force))?;
+        Ok(written)
+    }
+
+    #[pyo3(signature = (pattern, *, readable = true, writable = true, executable = true, mapped = false, alignment = 0))]
+    fn aob(&self, py: Python<'_>, pattern: &str, readable: bool, writable: bool, executable: bool, mapped: bool, alignment: usize) -> PyResult<Vec<usize>> {
+        let filter = PageFilter { mapped, readable, writable, executable };
+        Ok(py.allow_threads(|| self.inner.scanner().filter(filter).alignment(alignment).aob(pattern))?)
+    }
+
+    #[pyo3(signature = (text, *, encodings = 7, case_insensitive = false, readable = true, writable = true, executable = true, mapped = false, alignment = 0))]
+    fn string_scan(&self, py: Python<'_>, text: &str, encodings: u32, case_insensitive: bool, readable: bool, writable: bool, executable: bool, mapped: bool, alignment: usize) -> PyResult<Vec<PyStringMatch>> {
+        let filter = PageFilter { mapped, readable, writable, executable };
+        let matches = py.allow_threads(|| self.inner.scanner().filter(filter).alignment(alignment).string(text, encodings, case_insensitive))?;
+
+        Ok(matches.into_iter()
+            .map(|(address, encoding)| PyStringMatch {
+                address,
+                encoding: match encoding {
+                    TextEncoding::Ascii => "ascii",
+                    TextEncoding::Utf8 => "utf8",
+                    TextEncoding::Utf16Le => "utf16le",
+                },
+            })
+            .collect())
+    }
+
+    #[pyo3(signature = (pattern, *, max_match_len = 0, max_matches = 0, readable = true, writable = true, executable = true, mapped = false, alignment = 0))]
+    fn regex_scan(&self, py: Python<'_>, pattern: &str, max_match_len: usize, max_matches: usize, readable: bool, writable: bool, executable: bool, mapped: bool, alignment: usize) -> PyResult<Vec<PyRegexMatch>> {
+        let filter = PageFilter { mapped, readable, writable, executable };
+        let matches = py.allow_threads(|| self.inner.scanner().filter(filter).alignment(alignment).regex(pattern, max_match_len, max_matches))?;
+
+        Ok(matches.into_iter()
+            .map(|(address, length)| PyRegexMatch { address, length })
+            .collect())
+    }
+
+    /// `mode` is "rounded" or "truncated" to `decimals` (at most 15), or "epsilon". `candidates`
+    /// re-checks the given addresses instead of scanning the regions.
+    #[pyo3(signature = (value, *, double = false, mode = "rounded", decimals = 0, epsilon = 0.0, candidates = None, readable = true, writable = true, executable = true, mapped = false, alignment = 0))]
+    fn float_scan(&self, py: Python<'_>, value: f64, double: bool, mode: &str, decimals: u32, epsilon: f64, candidates: Option<Vec<usize>>, readable: bool, writable: bool, executable: bool, mapped: bool, alignment: usize) -> PyResult<Vec<PyFloatMatch>> {
+        let value_type = match double {
+            true => FloatType::F64,
+            false => FloatType::F32,
+        };
+
+        let mode = match mode {
+            "rounded" => FloatMode::Rounded { decimals: check_decimals(decimals)? },
+            "truncated" => FloatMode::Truncated { decimals: check_decimals(decimals)? },
+            "epsilon" if epsilon >= 0.0 => FloatMode::Epsilon { epsilon },
+            _ => return Err(MemoryError::InvalidScanParameters.into()),
+        };
+
+        let alignment = resolve_alignment(alignment, value_type.size())?;
+        let matcher = FloatMatcher { value_type, mode, value, alignment };
+        let filter = PageFilter { mapped, readable, writable, executable };
+
+        let matches = py.allow_threads(|| {
+            let scanner = self.inner.scanner().filter(filter);
+            match candidates.as_ref() {
+                Some(candidates) => scanner.float_candidates(&matcher, candidates),
+                None => scanner.float(&matcher),
+            }
+        });
+
+        Ok(matches.into_iter()
+            .map(|(address, value)| PyFloatMatch { address, value })
+            .collect())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Process(pid={})", self.inner.pid())
+    }
+}
+
+#[pyfunction]
+fn attach(pid: u32) -> PyResult<PyProcess> {
+    PyProcess::attach(pid)
+}
+
+#[pymodule]
+fn rsmem(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+
+    m.add_class::<PyProcess>()?;
+    m.add_class::<PyRegion>()?;
+    m.add_class::<PyStringMatch>()?;
+    m.add_class::<PyRegexMatch>()?;
+    m.add_class::<PyFloatMatch>()?;
+    m.add_function(wrap_pyfunction!(attach, m)?)?;
+
+    m.add("RsMemError", py.get_type::<RsMemError>())?;
+    m.add("ProcessError", py.get_type::<ProcessError>())?;
+    m.add("AccessError", py.get_type::<AccessError>())?;
+    m.add("PatternError", py.get_type::<PatternError>())?;
+    m.add("PatchError", py.get_type::<PatchError>())?;
+    m.add("AssemblyError", py.get_type::<AssemblyError>())?;
+    m.add("LayoutError", py.get_type::<LayoutError>())?;
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use regex::bytes::{Regex, RegexBuilder};
+use udbg::memory::MemoryPage;
+
+use crate::{definitions::IMemoryTarget, error::MemoryError};
+
+const CHUNK_SIZE: usize = 0x100000;
+pub const DEFAULT_MAX_MATCH_LEN: usize = 0x1000;
+
+pub fn compile(pattern: &str) -> Result<Regex, MemoryError> {
+    RegexBuilder::new(pattern)
+        .size_limit(1 << 24)
+        .build()
+        .map_err(|_| MemoryError::InvalidPattern)
+}
+
+// how far a window may grow past its chunk to find the end of a long match
+const MAX_EXTENSION: usize = 0x1000000;
+
+/// Runs the regex over the pages, merging adjacent ones into regions and reading them in chunks
+/// that overlap by `max_match_len` so matches crossing a chunk boundary are still found. A match
+/// running into the end of the window grows the window until it ends (up to 16 MiB), matches
+/// longer than `max_match_len` are reported with their length truncated to it. When
+/// `max_matches` is not 0 only the lowest `max_matches` addresses are returned. Only matches
+/// starting at a multiple of `alignment` are reported.
+pub fn scan(target: &IMemoryTarget, pages: &[MemoryPage], regex: &Regex, max_match_len: usize, max_matches: usize, alignment: usize) -> Vec<(usize, usize)> {
+    let max_match_len = match max_match_len {
+        0 => DEFAULT_MAX_MATCH_LEN,
+        len => len,
+    };
+
+    let mut matches: Vec<(usize, usize)> = merge_regions(pages)
+        .par_iter()
+        .flat_map_iter(|(base, size)| {
+            let region_end = base + size;
+            let mut matches = Vec::new();
+            let mut resume = *base;
+            let mut chunk = *base;
+
+            // every region stops at its own first `max_matches`, so the lowest ones overall survive
+            while chunk < region_end && (max_matches == 0 || matches.len() < max_matches) {
+                let chunk_end = (chunk + CHUNK_SIZE).min(region_end);
+                let mut window_end = (chunk_end.saturating_add(max_match_len)).min(region_end);
+                let mut buffer = target.read_bytes(chunk, window_end - chunk);
+
+                let mut position = align_up(resume.max(chunk), alignment) - chunk;
+                while let Some(mut m) = buffer.get(position..).and_then(|_| regex.find_at(&buffer, position)).map(|m| m.range()) {
+                    // the match may continue past the window, grow it while the region allows
+                    while m.end == buffer.len() && buffer.len() == window_end - chunk && window_end < region_end && window_end - chunk_end < MAX_EXTENSION {
+                        let extension = (window_end - chunk_end).max(1).saturating_mul(2).min(MAX_EXTENSION);
+                        window_end = (chunk_end + extension).min(region_end);
+                        buffer = target.read_bytes(chunk, window_end - chunk);
+
+                        match regex.find_at(&buffer, position) {
+                            Some(grown) => m = grown.range(),
+                            None => break,
+                        }
+                    }
+
+                    let start = chunk + m.start;
+                    if start >= chunk_end {
+                        break;
+                    }
+
+                    // retry from the next aligned address, a match may start inside this one
+                    if start % alignment != 0 {
+                        position = align_up(start + 1, alignment) - chunk;
+                        continue;
+                    }
+
+                    matches.push((start, m.len().min(max_match_len)));
+                    resume = chunk + m.end.max(m.start + 1);
+                    position = align_up(resume, alignment) - chunk;
+                }
+
+                // skip the chunks a long match already covered
+                chunk = chunk_end.max(resume.min(region_end));
+            }
+
+            matches
+        })
+        .collect();
+
+    matches.sort_by_key(|(address, _)| *address);
+    matches.dedup_by_key(|(address, _)| *address);
+    if max_matches > 0 {
+        matches.truncate(max_matches);
+    }
+
+    matches
+}
+
+fn align_up(address: usize, alignment: usize) -> usize {
+    (address + alignment - 1) / alignment * alignment
+}
+
+fn merge_regions(pages: &[MemoryPage]) -> Vec<(usize, usize)> {
+    let mut sorted = pages.iter().map(|page| (page.base, page.size)).collect::<Vec<_>>();
+    sorted.sort_by_key(|(base, _)| *base);
+
+    let mut regions: Vec<(usize, usize)> = Vec::new();
+    for (base, size) in sorted {
+        match regions.last_mut() {
+            Some((last_base, last_size)) if *last_base + *last_size == base => *last_size += size,
+            _ => regions.push((base, size)),
+        }
+    }
+
+    regions
+}
+
+// Regex match
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CRegexMatch {
+    pub address: usize,
+    pub length: usize,
+}
@@ -0,0 +1,192 @@
+// Remote syscalls, executed inside the target by hijacking its main thread with ptrace while
+// every other thread is stopped
+// ---------------------------------------------------------------
+use udbg::pe::*;
+
+const PAGE_SIZE: usize = 0x1000;
+
+// syscall; int3
+const SYSCALL_STUB: [u8; 3] = [0x0f, 0x05, 0xcc];
+
+pub fn mmap(pid: u32, size: usize, protection: u32, near_address: usize) -> Option<usize> {
+    let address = align_down(near_address);
+    let size = align_up(size);
+    let flags = (libc::MAP_PRIVATE | libc::MAP_ANONYMOUS) as usize;
+
+    syscall(pid, libc::SYS_mmap, [address, size, page_to_prot(protection), flags, usize::MAX, 0])
+}
+
+pub fn munmap(pid: u32, address: usize, size: usize) -> bool {
+    syscall(pid, libc::SYS_munmap, [align_down(address), align_up(size), 0, 0, 0, 0]).is_some()
+}
+
+pub fn mprotect(pid: u32, address: usize, size: usize, protection: u32) -> bool {
+    let start = align_down(address);
+    let size = align_up(address + size) - start;
+
+    syscall(pid, libc::SYS_mprotect, [start, size, page_to_prot(protection), 0, 0, 0]).is_some()
+}
+
+/// Maps windows style `PAGE_*` protection flags to posix `PROT_*` flags.
+pub fn page_to_prot(protection: u32) -> usize {
+    let readable = PAGE_READONLY | PAGE_READWRITE | PAGE_WRITECOPY
+        | PAGE_EXECUTE_READ | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY;
+    let writable = PAGE_READWRITE | PAGE_WRITECOPY | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY;
+    let executable = PAGE_EXECUTE | PAGE_EXECUTE_READ | PAGE_EXECUTE_READWRITE | PAGE_EXECUTE_WRITECOPY;
+
+    let mut prot = libc::PROT_NONE;
+    if protection & readable > 0 { prot |= libc::PROT_READ; }
+    if protection & writable > 0 { prot |= libc::PROT_WRITE; }
+    if protection & executable > 0 { prot |= libc::PROT_EXEC; }
+
+    prot as usize
+}
+
+fn syscall(pid: u32, number: libc::c_long, args: [usize; 6]) -> Option<usize> {
+    let pid = pid as libc::pid_t;
+
+    // a target udbg already traces from this process can't be attached a second time (EPERM),
+    // its threads are stopped through the existing attachment instead
+    let reuse = match tracer_pid(pid)? {
+        0 => false,
+        tracer if tracer == unsafe { libc::getpid() } => true,
+        _ => return None,
+    };
+
+    unsafe {
+        let stopped = stop_threads(pid, reuse);
+        let result = match stopped.iter().any(|thread| thread.tid == pid) {
+            true => inject(pid, number, args),
+            false => None,
+        };
+
+        resume_threads(&stopped, reuse);
+        result
+    }
+}
+
+struct StoppedThread {
+    tid: libc::pid_t,
+    // already in a ptrace stop of the existing tracer, left stopped afterwards
+    was_stopped: bool,
+}
+
+fn tracer_pid(pid: libc::pid_t) -> Option<libc::pid_t> {
+    std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("TracerPid:"))
+        .and_then(|tracer| tracer.trim().parse().ok())
+}
+
+fn thread_ids(pid: libc::pid_t) -> Vec<libc::pid_t> {
+    std::fs::read_dir(format!("/proc/{}/task", pid))
+        .map(|entries| entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+            .collect())
+        .unwrap_or_default()
+}
+
+/// `t` in `/proc/<pid>/task/<tid>/stat`, stopped by the tracer.
+fn is_trace_stopped(pid: libc::pid_t, tid: libc::pid_t) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/task/{}/stat", pid, tid))
+        .ok()
+        .and_then(|stat| stat.rsplit_once(')').and_then(|(_, fields)| fields.split_whitespace().next().map(|state| state == "t")))
+        .unwrap_or(false)
+}
+
+/// Stops every thread of the target, returns the threads that are in a ptrace stop.
+unsafe fn stop_threads(pid: libc::pid_t, reuse: bool) -> Vec<StoppedThread> {
+    let mut stopped = Vec::new();
+
+    for tid in thread_ids(pid) {
+        if reuse && is_trace_stopped(pid, tid) {
+            stopped.push(StoppedThread { tid, was_stopped: true });
+            continue;
+        }
+
+        let requested = match reuse {
+            true => libc::syscall(libc::SYS_tgkill, pid, tid, libc::SIGSTOP) == 0,
+            false => libc::ptrace(libc::PTRACE_ATTACH, tid, 0, 0) >= 0,
+        };
+
+        if !requested {
+            continue;
+        }
+
+        match wait_stopped(tid) {
+            true => stopped.push(StoppedThread { tid, was_stopped: false }),
+            false if !reuse => _ = libc::ptrace(libc::PTRACE_DETACH, tid, 0, 0),
+            false => {},
+        }
+    }
+
+    stopped
+}
+
+unsafe fn resume_threads(stopped: &[StoppedThread], reuse: bool) {
+    for thread in stopped.iter().filter(|thread| !thread.was_stopped) {
+        match reuse {
+            // continue without delivering the SIGSTOP used to stop the thread
+            true => libc::ptrace(libc::PTRACE_CONT, thread.tid, 0, 0),
+            false => libc::ptrace(libc::PTRACE_DETACH, thread.tid, 0, 0),
+        };
+    }
+}
+
+unsafe fn inject(pid: libc::pid_t, number: libc::c_long, args: [usize; 6]) -> Option<usize> {
+    let mut saved: libc::user_regs_struct = std::mem::zeroed();
+    if libc::ptrace(libc::PTRACE_GETREGS, pid, 0, &mut saved as *mut _) < 0 {
+        return None;
+    }
+
+    let ip = saved.rip as usize;
+
+    *libc::__errno_location() = 0;
+    let original = libc::ptrace(libc::PTRACE_PEEKTEXT, pid, ip, 0);
+    if original == -1 && *libc::__errno_location() != 0 {
+        return None;
+    }
+
+    let mut patched = original.to_ne_bytes();
+    patched[..SYSCALL_STUB.len()].copy_from_slice(&SYSCALL_STUB);
+    if libc::ptrace(libc::PTRACE_POKETEXT, pid, ip, i64::from_ne_bytes(patched)) < 0 {
+        return None;
+    }
+
+    let mut regs = saved;
+    regs.rax = number as u64;
+    regs.orig_rax = u64::MAX;
+    regs.rdi = args[0] as u64;
+    regs.rsi = args[1] as u64;
+    regs.rdx = args[2] as u64;
+    regs.r10 = args[3] as u64;
+    regs.r8 = args[4] as u64;
+    regs.r9 = args[5] as u64;
+
+    let executed = libc::ptrace(libc::PTRACE_SETREGS, pid, 0, &regs as *const _) >= 0
+        && libc::ptrace(libc::PTRACE_SINGLESTEP, pid, 0, 0) >= 0
+        && wait_stopped(pid)
+        && libc::ptrace(libc::PTRACE_GETREGS, pid, 0, &mut regs as *mut _) >= 0;
+
+    libc::ptrace(libc::PTRACE_POKETEXT, pid, ip, original);
+    libc::ptrace(libc::PTRACE_SETREGS, pid, 0, &saved as *const _);
+
+    let ret = regs.rax as i64;
+    match executed && !(-4095..0).contains(&ret) {
+        true => Some(ret as usize),
+        false => None,
+    }
+}
+
+unsafe fn wait_stopped(pid: libc::pid_t) -> bool {
+    let mut status = 0;
+    libc::waitpid(pid, &mut status, libc::__WALL) == pid && libc::WIFSTOPPED(status)
+}
+
+fn align_down(address: usize) -> usize {
+    address & !(PAGE_SIZE - 1)
+}
+
+fn align_up(address: usize) -> usize {
+    (address + PAGE_SIZE - 1) & !(PAGE_SIZE - 1)
+}
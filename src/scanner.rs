@@ -0,0 +1,165 @@
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use udbg::memory::MemoryPage;
+
+use crate::{
+    definitions::{IMemoryTarget, PageFilter},
+    disasm,
+    error::MemoryError,
+    float::FloatMatcher,
+    pattern::{decode_capture, find_aligned_occurrences, parse_pattern, resolve_alignment, Capture, CaptureMatch},
+    process::{Process, Region},
+    regex_scan,
+    text::{self, TextEncoding},
+};
+
+/// Searches the regions of a process matching a `PageFilter`, only reporting hits at multiples
+/// of the alignment (0 picks the natural alignment of what is searched).
+#[derive(Clone)]
+pub struct Scanner<'a> {
+    process: &'a Process,
+    filter: PageFilter,
+    alignment: usize,
+    module: Option<String>,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(process: &'a Process) -> Self {
+        Self { process, filter: PageFilter::default(), alignment: 0, module: None }
+    }
+
+    pub fn filter(mut self, filter: PageFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: usize) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Only searches the pages mapped from the module file `name`, see `Region::is_module`.
+    pub fn module(mut self, name: &str) -> Self {
+        self.module = Some(name.to_owned());
+        self
+    }
+
+    pub fn regions(&self) -> Vec<Region> {
+        self.process.regions()
+            .into_iter()
+            .filter(|region| region.matches(&self.filter))
+            .filter(|region| self.module.as_deref().map_or(true, |name| region.is_module(name)))
+            .collect()
+    }
+
+    fn pages(&self) -> Vec<MemoryPage> {
+        self.regions()
+            .into_iter()
+            .map(|region| region.page().clone())
+            .collect()
+    }
+
+    fn target(&self) -> &IMemoryTarget {
+        self.process.target().as_ref()
+    }
+
+    /// Addresses matching an AOB pattern, e.g. `48 8B 05 ?? ?? ?? ??`.
+    pub fn aob(&self, pattern: &str) -> Result<Vec<usize>, MemoryError> {
+        let alignment = resolve_alignment(self.alignment, 1)?;
+        let pattern = parse_pattern(pattern)?;
+
+        Ok(scan_pages(self.target(), &self.pages(), &pattern.bytes, alignment))
+    }
+
+    /// `aob` that also resolves the first `( .. )` group of every match into the address the
+    /// operand refers to, `None` when it could not be resolved.
+    pub fn aob_resolve(&self, pattern: &str, bitness: u32) -> Result<Vec<(usize, Option<usize>)>, MemoryError> {
+        let alignment = resolve_alignment(self.alignment, 1)?;
+        let pattern = parse_pattern(pattern)?;
+        let group = pattern.groups.first()
+            .ok_or(MemoryError::PatternMissingGroup)?;
+
+        Ok(scan_pages(self.target(), &self.pages(), &pattern.bytes, alignment)
+            .into_iter()
+            .map(|address| (address, disasm::resolve_operand(self.target(), address, group.offset, group.len, bitness)))
+            .collect())
+    }
+
+    /// `aob` returning the value of every `( .. )` / `(name: .. )` group, groups have to be at
+    /// most 8 bytes wide.
+    pub fn aob_capture(&self, pattern: &str) -> Result<Vec<CaptureMatch>, MemoryError> {
+        let alignment = resolve_alignment(self.alignment, 1)?;
+        let pattern = parse_pattern(pattern)?;
+        if pattern.groups.is_empty() {
+            return Err(MemoryError::PatternMissingGroup);
+        }
+
+        if pattern.groups.iter().any(|group| group.len > 8) {
+            return Err(MemoryError::CaptureGroupTooWide);
+        }
+
+        // the values come from the bytes that matched, not from a second read
+        Ok(scan_pages_with(self.target(), &self.pages(), &pattern.bytes, alignment, |address, bytes| {
+            let captures = pattern.groups.iter()
+                .map(|group| Capture {
+                    name: group.name.clone(),
+                    offset: group.offset,
+                    size: group.len,
+                    value: bytes.get(group.offset..group.offset + group.len).and_then(decode_capture),
+                })
+                .collect();
+
+            CaptureMatch { address, captures }
+        }))
+    }
+
+    /// Searches `text` in every encoding flagged in `encodings`, sorted by address.
+    pub fn string(&self, text: &str, encodings: u32, case_insensitive: bool) -> Result<Vec<(usize, TextEncoding)>, MemoryError> {
+        // 0 is resolved per encoding by the scan
+        let alignment = resolve_alignment(self.alignment, 0)?;
+        let needles = text::build_needles(text, encodings, case_insensitive);
+        if needles.is_empty() {
+            return Err(MemoryError::InvalidPattern);
+        }
+
+        Ok(text::scan(self.target(), &self.pages(), &needles, alignment))
+    }
+
+    /// Runs a bytes regex, returning the address and length of every match.
+    pub fn regex(&self, pattern: &str, max_match_len: usize, max_matches: usize) -> Result<Vec<(usize, usize)>, MemoryError> {
+        let alignment = resolve_alignment(self.alignment, 1)?;
+        let regex = regex_scan::compile(pattern)?;
+
+        Ok(regex_scan::scan(self.target(), &self.pages(), &regex, max_match_len, max_matches, alignment))
+    }
+
+    /// The alignment of the matcher is used instead of the scanner's.
+    pub fn float(&self, matcher: &FloatMatcher) -> Vec<(usize, f64)> {
+        matcher.scan(self.target(), &self.pages())
+    }
+
+    pub fn float_candidates(&self, matcher: &FloatMatcher, candidates: &[usize]) -> Vec<(usize, f64)> {
+        matcher.scan_candidates(self.target(), candidates)
+    }
+}
+
+fn scan_pages(target: &IMemoryTarget, pages: &[MemoryPage], pattern: &[Vec<u8>], alignment: usize) -> Vec<usize> {
+    scan_pages_with(target, pages, pattern, alignment, |address, _| address)
+}
+
+/// `scan_pages` handing every match to `map` along with the page bytes starting at it.
+fn scan_pages_with<T, F>(target: &IMemoryTarget, pages: &[MemoryPage], pattern: &[Vec<u8>], alignment: usize, map: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize, &[u8]) -> T + Sync,
+{
+    pages
+        .par_iter()
+        .flat_map_iter(|page| {
+            let buff = target.read_bytes(page.base, page.size);
+            find_aligned_occurrences(&buff, pattern, page.base, alignment)
+                .into_iter()
+                .map(|addr| map(page.base + addr, &buff[addr..]))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
@@ -0,0 +1,302 @@
+use std::{cell::RefCell, rc::Rc, sync::Arc, time::Duration};
+
+use mlua::{Function, Lua, Table, UserData, UserDataMethods, Value, Variadic};
+use rust_i18n::error::AsDetails;
+
+use crate::{definitions::PageFilter, error::MemoryError, process::Process};
+
+impl From<MemoryError> for mlua::Error {
+    fn from(value: MemoryError) -> Self {
+        mlua::Error::RuntimeError(value.as_details().message.clone())
+    }
+}
+
+/// `Process` as seen by scripts.
+struct LuaProcess {
+    inner: Process,
+    /// Processes the script attached itself, the ones it may detach.
+    attached: Rc<RefCell<Vec<Process>>>,
+}
+
+impl UserData for LuaProcess {
+    fn add_methods<'lua, M: UserDataMethods<'lua, Self>>(methods: &mut M) {
+        methods.add_method("pid", |_, this, ()| Ok(this.inner.pid()));
+
+        methods.add_method("regions", |lua, this, ()| {
+            let regions = lua.create_table()?;
+            for region in this.inner.regions() {
+                let entry = lua.create_table()?;
+                entry.set("base", region.base())?;
+                entry.set("size", region.size())?;
+                entry.set("protection", region.protection())?;
+                entry.set("usage", region.usage())?;
+                entry.set("readable", region.is_readable())?;
+                entry.set("writable", region.is_writable())?;
+                entry.set("executable", region.is_executable())?;
+                regions.push(entry)?;
+            }
+
+            Ok(regions)
+        });
+
+        methods.add_method("aob", |_, this, (pattern, options): (String, Option<Table>)| {
+            let (filter, alignment) = scan_options(options)?;
+            Ok(this.inner.scanner().filter(filter).alignment(alignment).aob(&pattern)?)
+        });
+
+        // addresses below are integers or expressions, e.g. `"[game.so+1F00]+18"` or `"player+40"`
+        methods.add_method("evaluate", |_, this, expression: String| {
+            Ok(this.inner.evaluate(&expression, 0)?)
+        });
+
+        methods.add_method("symbol", |_, this, name: String| Ok(this.inner.symbol(&name)));
+
+        methods.add_method("register_symbol", |_, this, (name, address): (String, Value)| {
+            Ok(match address {
+                Value::String(expression) => this.inner.define_symbol(&name, expression.to_str()?, 0)?,
+                address => {
+                    let address = this.address(address)?;
+                    this.inner.register_symbol(&name, address)?;
+                    address
+                }
+            })
+        });
+
+        methods.add_method("remove_symbol", |_, this, name: String| Ok(this.inner.remove_symbol(&name)));
+
+        // bytes as a lua string, shorter when the range is partially unreadable
+        methods.add_method("read", |lua, this, (address, size): (Value, usize)| {
+            lua.create_string(this.inner.read(this.address(address)?, size))
+        });
+
+        methods.add_method("write", |_, this, (address, bytes, force): (Value, mlua::String, Option<bool>)| {
+            let (written, _) = this.inner.write_with(this.address(address)?, bytes.as_bytes(), force.unwrap_or(false))?;
+            Ok(written)
+        });
+
+        methods.add_method("read_value", |_, this, (address, kind): (Value, String)| {
+            let size = value_size(&kind)?;
+            let bytes = this.inner.read_exact(this.address(address)?, size)?;
+            decode_value(&kind, &bytes)
+        });
+
+        methods.add_method("write_value", |_, this, (address, kind, value, force): (Value, String, Value, Option<bool>)| {
+            let bytes = encode_value(&kind, value)?;
+            let (written, _) = this.inner.write_with(this.address(address)?, &bytes, force.unwrap_or(false))?;
+            Ok(written)
+        });
+
+        // follows `[[address] + offsets[1]] + ...`, the last offset is added without a read
+        methods.add_method("follow", |_, this, (address, offsets): (Value, Variadic<usize>)| {
+            let mut address = this.address(address)?;
+            for offset in offsets.iter() {
+                let bytes = this.inner.read_exact(address, std::mem::size_of::<usize>())?;
+                address = usize::from_le_bytes(bytes.try_into().unwrap()).wrapping_add(*offset);
+            }
+
+            Ok(address)
+        });
+
+        // a no-op for the host's targets, they stay attached to the host
+        methods.add_method("detach", |_, this, ()| {
+            let mut attached = this.attached.borrow_mut();
+            if let Some(index) = attached.iter().position(|n| Arc::ptr_eq(n.target(), this.inner.target())) {
+                attached.remove(index).detach();
+            }
+
+            Ok(())
+        });
+    }
+}
+
+impl LuaProcess {
+    fn address(&self, value: Value) -> mlua::Result<usize> {
+        match value {
+            Value::Integer(address) => Ok(address as usize),
+            Value::Number(address) if address >= 0.0 && address.fract() == 0.0 => Ok(address as usize),
+            Value::String(expression) => Ok(self.inner.evaluate(expression.to_str()?, 0)?),
+            _ => Err(mlua::Error::RuntimeError("expected an address or an expression".to_owned())),
+        }
+    }
+}
+
+fn scan_options(options: Option<Table>) -> mlua::Result<(PageFilter, usize)> {
+    let defaults = PageFilter::default();
+    let Some(options) = options else {
+        return Ok((defaults, 0));
+    };
+
+    let filter = PageFilter {
+        mapped: options.get::<_, Option<bool>>("mapped")?.unwrap_or(defaults.mapped),
+        readable: options.get::<_, Option<bool>>("readable")?.unwrap_or(defaults.readable),
+        writable: options.get::<_, Option<bool>>("writable")?.unwrap_or(defaults.writable),
+        executable: options.get::<_, Option<bool>>("executable")?.unwrap_or(defaults.executable),
+    };
+
+    Ok((filter, options.get::<_, Option<usize>>("alignment")?.unwrap_or(0)))
+}
+
+fn value_size(kind: &str) -> mlua::Result<usize> {
+    match kind {
+        "i8" | "u8" => Ok(1),
+        "i16" | "u16" => Ok(2),
+        "i32" | "u32" | "f32" => Ok(4),
+        "i64" | "u64" | "f64" => Ok(8),
+        "ptr" => Ok(std::mem::size_of::<usize>()),
+        _ => Err(mlua::Error::RuntimeError(format!("unknown value type {:?}", kind))),
+    }
+}
+
+fn decode_value<'lua>(kind: &str, bytes: &[u8]) -> mlua::Result<Value<'lua>> {
+    macro_rules! int {
+        ($ty:ty) => {
+            Value::Integer(<$ty>::from_le_bytes(bytes.try_into().unwrap()) as i64)
+        };
+    }
+
+    Ok(match kind {
+        "i8" => int!(i8),
+        "u8" => int!(u8),
+        "i16" => int!(i16),
+        "u16" => int!(u16),
+        "i32" => int!(i32),
+        "u32" => int!(u32),
+        "i64" => int!(i64),
+        // wraps above i64::MAX like lua's own integers
+        "u64" => int!(u64),
+        "ptr" => int!(usize),
+        "f32" => Value::Number(f32::from_le_bytes(bytes.try_into().unwrap()) as f64),
+        "f64" => Value::Number(f64::from_le_bytes(bytes.try_into().unwrap())),
+        _ => return Err(mlua::Error::RuntimeError(format!("unknown value type {:?}", kind))),
+    })
+}
+
+fn encode_value(kind: &str, value: Value) -> mlua::Result<Vec<u8>> {
+    let integer = || -> mlua::Result<i64> {
+        match &value {
+            Value::Integer(value) => Ok(*value),
+            Value::Number(value) if value.fract() == 0.0 => Ok(*value as i64),
+            _ => Err(mlua::Error::RuntimeError(format!("expected an integer for {:?}", kind))),
+        }
+    };
+
+    let number = || -> mlua::Result<f64> {
+        match &value {
+            Value::Integer(value) => Ok(*value as f64),
+            Value::Number(value) => Ok(*value),
+            _ => Err(mlua::Error::RuntimeError(format!("expected a number for {:?}", kind))),
+        }
+    };
+
+    // out of range values are errors instead of being truncated
+    macro_rules! int {
+        ($ty:ty) => {{
+            let value = integer()?;
+            <$ty>::try_from(value)
+                .map_err(|_| mlua::Error::RuntimeError(format!("{} is out of range for {:?}", value, kind)))?
+                .to_le_bytes()
+                .to_vec()
+        }};
+    }
+
+    Ok(match kind {
+        "i8" => int!(i8),
+        "u8" => int!(u8),
+        "i16" => int!(i16),
+        "u16" => int!(u16),
+        "i32" => int!(i32),
+        "u32" => int!(u32),
+        // negative values are the ones `read_value` returns above i64::MAX
+        "i64" | "u64" => integer()?.to_le_bytes().to_vec(),
+        "ptr" => (integer()? as usize).to_le_bytes().to_vec(),
+        "f32" => (number()? as f32).to_le_bytes().to_vec(),
+        "f64" => number()?.to_le_bytes().to_vec(),
+        _ => return Err(mlua::Error::RuntimeError(format!("unknown value type {:?}", kind))),
+    })
+}
+
+/// Runs `source` in a fresh Lua state with the `rsmem` module loaded and returns what it printed.
+/// Processes the script attached itself are detached once it ends, even when it fails.
+pub fn run(source: &str, name: &str) -> Result<String, ScriptError> {
+    let lua = Lua::new();
+    let output = Rc::new(RefCell::new(String::new()));
+    let attached = Rc::new(RefCell::new(Vec::<Process>::new()));
+
+    let result = install(&lua, &output, &attached)
+        .and_then(|_| lua.load(source).set_name(name).exec());
+
+    for process in attached.borrow().iter() {
+        process.detach();
+    }
+
+    result
+        .map(|_| output.take())
+        .map_err(ScriptError::Lua)
+}
+
+/// `run` for a script file.
+pub fn load(path: &str) -> Result<String, ScriptError> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|_| ScriptError::Memory(MemoryError::FailedToReadScript))?;
+
+    run(&source, &format!("@{}", path))
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Memory(MemoryError),
+    /// Compile or runtime error of the script, with its traceback.
+    Lua(mlua::Error),
+}
+
+fn install(lua: &Lua, output: &Rc<RefCell<String>>, attached: &Rc<RefCell<Vec<Process>>>) -> mlua::Result<()> {
+    let globals = lua.globals();
+
+    let sink = output.clone();
+    globals.set("print", lua.create_function(move |lua, args: Variadic<Value>| {
+        let tostring: Function = lua.globals().get("tostring")?;
+        let mut line = Vec::with_capacity(args.len());
+        for arg in args {
+            // the output is handed out as a C string, it can't carry nuls
+            let text = tostring.call::<_, mlua::String>(arg)?;
+            line.push(text.to_string_lossy().replace('\0', "\u{fffd}"));
+        }
+
+        let mut sink = sink.borrow_mut();
+        sink.push_str(&line.join("\t"));
+        sink.push('\n');
+        Ok(())
+    })?)?;
+
+    let module = lua.create_table()?;
+
+    // reuses the target when the script or the host (through `attach`) already attached the process
+    let owned = attached.clone();
+    module.set("attach", lua.create_function(move |_, pid: u32| {
+        let known = owned.borrow().iter().find(|process| process.pid() == pid).cloned();
+        let process = match known.or_else(|| crate::find_target(pid)) {
+            Some(process) => process,
+            None => {
+                let process = Process::attach(pid)?;
+                owned.borrow_mut().push(process.clone());
+                process
+            }
+        };
+
+        Ok(LuaProcess { inner: process, attached: owned.clone() })
+    })?)?;
+
+    let owned = attached.clone();
+    module.set("target", lua.create_function(move |_, handle: usize| {
+        crate::validate_target_handle(handle)
+            .map(|process| LuaProcess { inner: process, attached: owned.clone() })
+            .map_err(|_| MemoryError::InvalidTargetHandle.into())
+    })?)?;
+
+    module.set("sleep", lua.create_function(|_, milliseconds: u64| {
+        std::thread::sleep(Duration::from_millis(milliseconds));
+        Ok(())
+    })?)?;
+
+    globals.set("rsmem", module)
+}
@@ -0,0 +1,148 @@
+use iced_x86::{Decoder, DecoderOptions, OpKind};
+use udbg::memory::MemoryPage;
+
+use crate::{definitions::IMemoryTarget, disasm::{normalize_bitness, MAX_INSTRUCTION_LENGTH}, error::MemoryError, pattern::find_all_occurrences};
+
+// anything below is more likely a small constant than an address
+const MIN_POINTER: u64 = 0x10000;
+
+// longer signatures are not useful, and `max_len` sizes the code read
+const MAX_SIGNATURE_LENGTH: usize = 256;
+
+/// Builds an AOB pattern for the code at `address` that is unique within its module. Operands
+/// that change between builds, rel32 branches, rip relative and absolute displacements and
+/// immediates pointing into mapped memory, are wildcarded.
+pub fn make_signature(target: &IMemoryTarget, address: usize, max_len: usize, bitness: u32) -> Result<String, MemoryError> {
+    let pages = target.collect_memory_info();
+    let module = module_pages(&pages, address);
+    if module.is_empty() {
+        return Err(MemoryError::FailedtoReadProcessMemory);
+    }
+
+    let max_len = max_len.min(MAX_SIGNATURE_LENGTH);
+    let code = target.read_bytes(address, max_len.saturating_add(MAX_INSTRUCTION_LENGTH));
+    let regions = read_regions(target, &module);
+
+    let mut pattern: Vec<Vec<u8>> = Vec::new();
+    let mut decoder = Decoder::with_ip(normalize_bitness(bitness), &code, address as u64, DecoderOptions::NONE);
+
+    while decoder.can_decode() && pattern.len() < max_len {
+        let position = decoder.position();
+        let instruction = decoder.decode();
+        if instruction.is_invalid() {
+            break;
+        }
+
+        let offsets = decoder.get_constant_offsets(&instruction);
+        let mut wildcards = vec![false; instruction.len()];
+        let mut mark = |offset: usize, size: usize| wildcards[offset..offset + size].fill(true);
+
+        if offsets.has_displacement() {
+            let absolute = instruction.is_ip_rel_memory_operand()
+                || instruction.memory_base() == iced_x86::Register::None && instruction.memory_index() == iced_x86::Register::None
+                || is_pointer(&pages, instruction.memory_displacement64());
+
+            if absolute {
+                mark(offsets.displacement_offset(), offsets.displacement_size());
+            }
+        }
+
+        if offsets.has_immediate() {
+            let is_rel32 = matches!(instruction.op0_kind(), OpKind::NearBranch32 | OpKind::NearBranch64)
+                && offsets.immediate_size() == 4;
+            let is_address = offsets.immediate_size() >= 4
+                && (0..instruction.op_count()).any(|i| is_immediate(instruction.op_kind(i)) && is_pointer(&pages, instruction.immediate(i)));
+
+            if is_rel32 || is_address {
+                mark(offsets.immediate_offset(), offsets.immediate_size());
+            }
+        }
+
+        if offsets.has_immediate2() && offsets.immediate_size2() >= 4 {
+            mark(offsets.immediate_offset2(), offsets.immediate_size2());
+        }
+
+        let bytes = &code[position..position + instruction.len()];
+        pattern.extend(bytes.iter().zip(wildcards).map(|(byte, wildcard)| match wildcard {
+            true => Vec::new(),
+            false => vec![*byte],
+        }));
+
+        let trimmed = trim(&pattern[..pattern.len().min(max_len)]);
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let hits: usize = regions.iter()
+            .map(|buffer| find_all_occurrences(buffer, trimmed).len())
+            .sum();
+
+        if hits == 1 {
+            return Ok(format_pattern(trimmed));
+        }
+    }
+
+    Err(MemoryError::SignatureNotUnique)
+}
+
+/// Pages of the module the address belongs to, or of its allocation when it is not in a module.
+fn module_pages(pages: &[MemoryPage], address: usize) -> Vec<MemoryPage> {
+    let Some(page) = pages.iter().find(|page| page.base <= address && address < page.base + page.size) else {
+        return Vec::new();
+    };
+
+    pages.iter()
+        .filter(|other| match (&page.info, &other.info) {
+            (Some(module), Some(other)) => module == other,
+            _ => other.alloc_base == page.alloc_base,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Reads the pages, contiguous readable pages end up in one buffer so matches straddling a page
+/// boundary are counted too.
+fn read_regions(target: &IMemoryTarget, pages: &[MemoryPage]) -> Vec<Vec<u8>> {
+    let mut pages = pages.to_vec();
+    pages.sort_by_key(|page| page.base);
+
+    let mut regions: Vec<Vec<u8>> = Vec::new();
+    let mut end = None;
+    for page in pages.iter() {
+        let bytes = target.read_bytes(page.base, page.size);
+        let complete = bytes.len() == page.size;
+
+        match regions.last_mut() {
+            Some(region) if end == Some(page.base) => region.extend(bytes),
+            _ => regions.push(bytes),
+        }
+
+        // an unreadable tail breaks the run
+        end = complete.then(|| page.base + page.size);
+    }
+
+    regions
+}
+
+fn is_pointer(pages: &[MemoryPage], value: u64) -> bool {
+    value >= MIN_POINTER && pages.iter().any(|page| (page.base as u64) <= value && value < (page.base + page.size) as u64)
+}
+
+fn is_immediate(kind: OpKind) -> bool {
+    matches!(kind, OpKind::Immediate32 | OpKind::Immediate64 | OpKind::Immediate32to64)
+}
+
+fn trim(pattern: &[Vec<u8>]) -> &[Vec<u8>] {
+    let end = pattern.iter().rposition(|byte| !byte.is_empty()).map_or(0, |i| i + 1);
+    &pattern[..end]
+}
+
+pub fn format_pattern(pattern: &[Vec<u8>]) -> String {
+    pattern.iter()
+        .map(|byte| match byte.first() {
+            Some(byte) => format!("{:02X}", byte),
+            None => "??".to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use log::warn;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::{
+    definitions::{ArcM, IMemoryTarget},
+    error::MemoryError,
+    expr::{self, is_name_char, parse_hex},
+    layout::deserialize_number,
+    process::Process,
+};
+
+lazy_static! {
+    // named addresses of attached targets in registration order, keyed by pid
+    static ref SYMBOLS: Lazy<ArcM<HashMap<u32, Vec<Symbol>>>> = Lazy::new(|| ArcM::default());
+}
+
+/// A named address. `expression` is what the address was evaluated from, symbol files store it
+/// so the addresses are evaluated again against the process they are loaded into.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Symbol {
+    pub name: String,
+    #[serde(default, serialize_with = "serialize_address", deserialize_with = "deserialize_number")]
+    pub address: usize,
+    pub expression: String,
+    #[serde(default, deserialize_with = "deserialize_number")]
+    pub pointer_size: usize,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SymbolFile {
+    #[serde(default)]
+    symbols: Vec<Symbol>,
+}
+
+fn serialize_address<S: Serializer>(address: &usize, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{:X}", address))
+}
+
+fn validate_name(name: &str) -> Result<(), MemoryError> {
+    // symbols are looked up before numbers, a name like `10` would change every `[base+10]`
+    match !name.is_empty() && name.chars().all(is_name_char) && parse_hex(name).is_none() {
+        true => Ok(()),
+        false => Err(MemoryError::InvalidSymbolName),
+    }
+}
+
+/// Names an address of the target, replacing an earlier symbol with the same name. Names are
+/// made of `A-Z a-z 0-9 _ . @ $` so expressions can use them without quotes, and can't be a
+/// number.
+pub fn register(target: &IMemoryTarget, symbol: Symbol) -> Result<(), MemoryError> {
+    validate_name(&symbol.name)?;
+
+    let mut registries = SYMBOLS.lock();
+    let symbols = registries.entry(target.pid()).or_default();
+
+    match symbols.iter_mut().find(|known| known.name == symbol.name) {
+        Some(known) => *known = symbol,
+        None => symbols.push(symbol),
+    }
+
+    Ok(())
+}
+
+/// Evaluates `expression` and names the result, see `expr::evaluate`. Returns the address.
+pub fn define(process: &Process, name: &str, expression: &str, pointer_size: usize) -> Result<usize, MemoryError> {
+    validate_name(name)?;
+
+    let address = expr::evaluate(process, expression, pointer_size)?;
+    register(process.target().as_ref(), Symbol {
+        name: name.to_owned(),
+        address,
+        expression: expression.to_owned(),
+        pointer_size,
+    })?;
+
+    Ok(address)
+}
+
+pub fn lookup(target: &IMemoryTarget, name: &str) -> Option<usize> {
+    SYMBOLS.lock()
+        .get(&target.pid())
+        .and_then(|symbols| symbols.iter().find(|symbol| symbol.name == name))
+        .map(|symbol| symbol.address)
+}
+
+/// Symbols of the target in registration order.
+pub fn list(target: &IMemoryTarget) -> Vec<Symbol> {
+    SYMBOLS.lock()
+        .get(&target.pid())
+        .cloned()
+        .unwrap_or_default()
+}
+
+pub fn remove(target: &IMemoryTarget, name: &str) -> bool {
+    let mut registries = SYMBOLS.lock();
+    let Some(symbols) = registries.get_mut(&target.pid()) else {
+        return false;
+    };
+
+    let count = symbols.len();
+    symbols.retain(|symbol| symbol.name != name);
+    symbols.len() != count
+}
+
+/// Writes the symbols of the target to `path` as json (`format` 0) or toml (`format` 1).
+pub fn save(target: &IMemoryTarget, path: &str, format: u32) -> Result<(), MemoryError> {
+    let file = SymbolFile { symbols: list(target) };
+    let text = match format {
+        0 => serde_json::to_string_pretty(&file).map_err(|_| MemoryError::FailedToWriteSymbols)?,
+        1 => toml::to_string_pretty(&file).map_err(|_| MemoryError::FailedToWriteSymbols)?,
+        _ => return Err(MemoryError::FailedToWriteSymbols),
+    };
+
+    std::fs::write(path, text)
+        .map_err(|_| MemoryError::FailedToWriteSymbols)
+}
+
+/// Reads a file written by `save` and defines its symbols in order, evaluating their expressions
+/// against the process. Symbols that fail to evaluate are skipped. Returns the number of defined
+/// symbols.
+pub fn load(process: &Process, path: &str, format: u32) -> Result<usize, MemoryError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|_| MemoryError::FailedToReadSymbols)?;
+
+    let file: SymbolFile = match format {
+        0 => serde_json::from_str(&text).map_err(|_| MemoryError::FailedToReadSymbols)?,
+        1 => toml::from_str(&text).map_err(|_| MemoryError::FailedToReadSymbols)?,
+        _ => return Err(MemoryError::FailedToReadSymbols),
+    };
+
+    let defined = file.symbols.iter()
+        .filter(|symbol| match define(process, &symbol.name, &symbol.expression, symbol.pointer_size) {
+            Ok(_) => true,
+            Err(err) => {
+                warn!("skipping the symbol {:?} = {:?}: {:?}", symbol.name, symbol.expression, err);
+                false
+            }
+        })
+        .count();
+
+    Ok(defined)
+}
+
+/// Forgets every symbol of the target, called on detach.
+pub fn release(target: &IMemoryTarget) {
+    SYMBOLS.lock().remove(&target.pid());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_names_that_read_as_numbers() {
+        for name in ["10", "BEEF", "ff", "0x10", ""] {
+            assert!(matches!(validate_name(name), Err(MemoryError::InvalidSymbolName)), "{}", name);
+        }
+
+        for name in ["player", "hp_10", "game.so", "x10", "@base"] {
+            assert!(validate_name(name).is_ok(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,449 @@
+use std::collections::{BTreeMap, HashMap};
+
+use lazy_static::lazy_static;
+use once_cell::sync::Lazy;
+use rust_i18n::error::AsDetails;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::{
+    definitions::ArcM,
+    error::MemoryError,
+    layout::deserialize_number,
+    process::{self, Process, Region},
+    symbol,
+};
+
+pub type TableId = usize;
+
+/// Upper bound of `Entry::length`, every evaluation reads that many bytes (units for `wstring`).
+pub(crate) const MAX_VALUE_LENGTH: usize = 0x10000;
+
+lazy_static! {
+    static ref TABLES: Lazy<ArcM<TableRegistry>> = Lazy::new(|| ArcM::default());
+}
+
+#[derive(Default)]
+struct TableRegistry {
+    next_id: TableId,
+    tables: BTreeMap<TableId, Table>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    I8, U8, I16, U16, I32, U32, I64, U64,
+    F32, F64,
+    /// pointer sized integer, see `Table::pointer_size`
+    Pointer,
+    /// nul terminated, at most `length` bytes
+    CString,
+    /// nul terminated utf-16le, at most `length` code units
+    WString,
+    /// `length` raw bytes, shown and frozen as `"90 90 EB"`
+    Bytes,
+}
+
+/// Where an entry lives: a registered `symbol`, `module`, the first match of `signature` (inside
+/// `module` when both are set) or 0, plus `offset`. Each of `offsets` then dereferences the
+/// address and adds the offset, like a Cheat Engine pointer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AddressSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default, serialize_with = "serialize_offset", deserialize_with = "deserialize_offset")]
+    pub offset: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", serialize_with = "serialize_offsets", deserialize_with = "deserialize_offsets")]
+    pub offsets: Vec<i64>,
+}
+
+/// Value written on every evaluation, an integer, a float or a text (bytes as `"90 90"`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FreezeValue {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub description: String,
+    pub address: AddressSpec,
+    #[serde(rename = "type")]
+    pub value_type: ValueType,
+    #[serde(default, skip_serializing_if = "is_zero", deserialize_with = "deserialize_number")]
+    pub length: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freeze: Option<FreezeValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Table {
+    #[serde(default = "default_pointer_size", deserialize_with = "deserialize_number")]
+    pub pointer_size: usize,
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self { pointer_size: default_pointer_size(), entries: Vec::new() }
+    }
+}
+
+/// Result of evaluating an entry, `error` tells why the address or value is missing.
+#[derive(Debug, Clone, Serialize)]
+pub struct EntryValue {
+    pub description: String,
+    pub address: Option<usize>,
+    pub value: Option<Value>,
+    pub error: Option<String>,
+}
+
+fn default_pointer_size() -> usize {
+    std::mem::size_of::<usize>()
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+fn parse_offset(text: &str) -> Result<i64, std::num::ParseIntError> {
+    let (negative, digits) = match text.trim().strip_prefix('-') {
+        Some(digits) => (true, digits.trim()),
+        None => (false, text.trim()),
+    };
+
+    let value = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => i64::from_str_radix(hex, 16)?,
+        None => digits.parse::<i64>()?,
+    };
+
+    Ok(if negative { -value } else { value })
+}
+
+fn format_offset(value: i64) -> String {
+    match value < 0 {
+        true => format!("-0x{:X}", value.unsigned_abs()),
+        false => format!("0x{:X}", value),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Offset {
+    Int(i64),
+    Text(String),
+}
+
+impl Offset {
+    fn into_i64<E: serde::de::Error>(self) -> Result<i64, E> {
+        match self {
+            Offset::Int(value) => Ok(value),
+            Offset::Text(text) => parse_offset(&text).map_err(E::custom),
+        }
+    }
+}
+
+// offsets are written as hex strings, `"-0x10"` included
+fn deserialize_offset<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    Offset::deserialize(deserializer)?.into_i64()
+}
+
+fn deserialize_offsets<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<i64>, D::Error> {
+    Vec::<Offset>::deserialize(deserializer)?
+        .into_iter()
+        .map(Offset::into_i64)
+        .collect()
+}
+
+fn serialize_offset<S: Serializer>(value: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_offset(*value))
+}
+
+fn serialize_offsets<S: Serializer>(values: &[i64], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(values.iter().map(|value| format_offset(*value)))
+}
+
+impl Table {
+    /// Parses a json (`format` 0) or toml (`format` 1) table.
+    pub fn parse(source: &str, format: u32) -> Result<Self, MemoryError> {
+        let table: Table = match format {
+            0 => serde_json::from_str(source).map_err(|_| MemoryError::InvalidTable)?,
+            1 => toml::from_str(source).map_err(|_| MemoryError::InvalidTable)?,
+            _ => return Err(MemoryError::InvalidTable),
+        };
+
+        let lengths_valid = table.entries.iter()
+            .all(|entry| entry.length <= MAX_VALUE_LENGTH);
+
+        match [4, 8].contains(&table.pointer_size) && lengths_valid {
+            true => Ok(table),
+            false => Err(MemoryError::InvalidTable),
+        }
+    }
+
+    pub fn to_text(&self, format: u32) -> Result<String, MemoryError> {
+        match format {
+            0 => serde_json::to_string_pretty(self).map_err(|_| MemoryError::InvalidTable),
+            1 => toml::to_string_pretty(self).map_err(|_| MemoryError::InvalidTable),
+            _ => Err(MemoryError::InvalidTable),
+        }
+    }
+
+    /// Resolves every entry against the process, writes the freeze values and reads the
+    /// current ones. Modules and signatures are looked up once per call.
+    pub fn evaluate(&self, process: &Process) -> Vec<EntryValue> {
+        let mut resolver = Resolver::new(process, self.pointer_size);
+
+        self.entries.iter()
+            .map(|entry| {
+                let mut result = EntryValue { description: entry.description.clone(), address: None, value: None, error: None };
+
+                let address = match resolver.resolve(&entry.address) {
+                    Ok(address) => address,
+                    Err(err) => {
+                        result.error = Some(err.as_details().message.clone());
+                        return result;
+                    }
+                };
+
+                result.address = Some(address);
+                if let Some(freeze) = entry.freeze.as_ref() {
+                    let written = encode_value(entry, self.pointer_size, freeze)
+                        .and_then(|bytes| process.write_with(address, &bytes, false));
+
+                    if let Err(err) = written {
+                        result.error = Some(err.as_details().message.clone());
+                    }
+                }
+
+                match read_value(process, entry, self.pointer_size, address) {
+                    Ok(value) => result.value = Some(value),
+                    Err(err) => result.error = Some(err.as_details().message.clone()),
+                }
+
+                result
+            })
+            .collect()
+    }
+}
+
+/// Resolves `AddressSpec`s, caching the memory map and signature matches.
+pub(crate) struct Resolver<'a> {
+    process: &'a Process,
+    pointer_size: usize,
+    regions: Option<Vec<Region>>,
+    signatures: HashMap<(Option<String>, String), Option<usize>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub(crate) fn new(process: &'a Process, pointer_size: usize) -> Self {
+        Self { process, pointer_size, regions: None, signatures: HashMap::new() }
+    }
+
+    pub(crate) fn process(&self) -> &'a Process {
+        self.process
+    }
+
+    pub(crate) fn module_base(&mut self, name: &str) -> Result<usize, MemoryError> {
+        let regions = self.regions.get_or_insert_with(|| self.process.regions());
+        process::find_module_base(regions, name)
+            .ok_or(MemoryError::ModuleNotFound)
+    }
+
+    pub(crate) fn signature(&mut self, module: Option<&str>, pattern: &str) -> Result<usize, MemoryError> {
+        let key = (module.map(str::to_owned), pattern.to_owned());
+        if let Some(address) = self.signatures.get(&key) {
+            return address.ok_or(MemoryError::SignatureNotFound);
+        }
+
+        let scanner = match module {
+            Some(module) => self.process.scanner().module(module),
+            None => self.process.scanner(),
+        };
+
+        let address = scanner.aob(pattern)?.first().copied();
+        self.signatures.insert(key, address);
+        address.ok_or(MemoryError::SignatureNotFound)
+    }
+
+    /// Truncates an address to the pointer size, 32 bit address arithmetic wraps at 4 GiB.
+    pub(crate) fn wrap(&self, address: usize) -> usize {
+        match self.pointer_size {
+            4 => address & u32::MAX as usize,
+            _ => address,
+        }
+    }
+
+    pub(crate) fn read_pointer(&self, address: usize) -> Result<usize, MemoryError> {
+        let bytes = self.process.read_exact(self.wrap(address), self.pointer_size)?;
+        Ok(match self.pointer_size {
+            4 => u32::from_le_bytes(bytes.try_into().unwrap()) as usize,
+            _ => u64::from_le_bytes(bytes.try_into().unwrap()) as usize,
+        })
+    }
+
+    pub(crate) fn resolve(&mut self, spec: &AddressSpec) -> Result<usize, MemoryError> {
+        let base = match (spec.symbol.as_deref(), spec.module.as_deref(), spec.signature.as_deref()) {
+            (Some(name), _, _) => symbol::lookup(self.process.target().as_ref(), name)
+                .ok_or(MemoryError::SymbolNotFound)?,
+            (None, module, Some(signature)) => self.signature(module, signature)?,
+            (None, Some(module), None) => self.module_base(module)?,
+            (None, None, None) => 0,
+        };
+
+        let mut address = base.wrapping_add_signed(spec.offset as isize);
+        for offset in spec.offsets.iter() {
+            address = self.read_pointer(address)?.wrapping_add_signed(*offset as isize);
+        }
+
+        Ok(self.wrap(address))
+    }
+}
+
+fn value_size(entry: &Entry, pointer_size: usize) -> usize {
+    match entry.value_type {
+        ValueType::I8 | ValueType::U8 => 1,
+        ValueType::I16 | ValueType::U16 => 2,
+        ValueType::I32 | ValueType::U32 | ValueType::F32 => 4,
+        ValueType::I64 | ValueType::U64 | ValueType::F64 => 8,
+        ValueType::Pointer => pointer_size,
+        ValueType::CString | ValueType::Bytes => entry.length,
+        ValueType::WString => entry.length * 2,
+    }
+}
+
+fn read_value(process: &Process, entry: &Entry, pointer_size: usize, address: usize) -> Result<Value, MemoryError> {
+    let size = value_size(entry, pointer_size);
+
+    // strings may end before an unreadable page, the fixed size types may not
+    let bytes = match entry.value_type {
+        ValueType::CString | ValueType::WString => process.read(address, size),
+        _ => process.read_exact(address, size)?,
+    };
+
+    if bytes.is_empty() && size > 0 {
+        return Err(MemoryError::FailedtoReadProcessMemory);
+    }
+
+    macro_rules! int {
+        ($ty:ty) => {
+            Value::from(<$ty>::from_le_bytes(bytes[..].try_into().unwrap()))
+        };
+    }
+
+    Ok(match entry.value_type {
+        ValueType::I8 => int!(i8),
+        ValueType::U8 => int!(u8),
+        ValueType::I16 => int!(i16),
+        ValueType::U16 => int!(u16),
+        ValueType::I32 => int!(i32),
+        ValueType::U32 => int!(u32),
+        ValueType::I64 => int!(i64),
+        ValueType::U64 => int!(u64),
+        ValueType::F32 => Value::from(f32::from_le_bytes(bytes[..].try_into().unwrap()) as f64),
+        ValueType::F64 => Value::from(f64::from_le_bytes(bytes[..].try_into().unwrap())),
+        ValueType::Pointer => match pointer_size {
+            4 => int!(u32),
+            _ => int!(u64),
+        },
+        ValueType::CString => {
+            let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+            Value::from(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        },
+        ValueType::WString => {
+            let units = bytes.chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .take_while(|unit| *unit != 0)
+                .collect::<Vec<_>>();
+
+            Value::from(String::from_utf16_lossy(&units))
+        },
+        ValueType::Bytes => Value::from(bytes.iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ")),
+    })
+}
+
+fn encode_value(entry: &Entry, pointer_size: usize, value: &FreezeValue) -> Result<Vec<u8>, MemoryError> {
+    let integer = || match value {
+        FreezeValue::Integer(value) => Ok(*value),
+        FreezeValue::Float(value) if value.fract() == 0.0 => Ok(*value as i64),
+        _ => Err(MemoryError::InvalidTable),
+    };
+
+    let float = || match value {
+        FreezeValue::Integer(value) => Ok(*value as f64),
+        FreezeValue::Float(value) => Ok(*value),
+        _ => Err(MemoryError::InvalidTable),
+    };
+
+    let text = || match value {
+        FreezeValue::Text(text) => Ok(text.as_str()),
+        _ => Err(MemoryError::InvalidTable),
+    };
+
+    // out of range values are rejected instead of being truncated
+    macro_rules! int {
+        ($ty:ty) => {
+            <$ty>::try_from(integer()?).map_err(|_| MemoryError::InvalidTable)?.to_le_bytes().to_vec()
+        };
+    }
+
+    let bytes = match entry.value_type {
+        ValueType::I8 => int!(i8),
+        ValueType::U8 => int!(u8),
+        ValueType::I16 => int!(i16),
+        ValueType::U16 => int!(u16),
+        ValueType::I32 => int!(i32),
+        ValueType::U32 => int!(u32),
+        ValueType::I64 => int!(i64),
+        ValueType::U64 => int!(u64),
+        ValueType::F32 => (float()? as f32).to_le_bytes().to_vec(),
+        ValueType::F64 => float()?.to_le_bytes().to_vec(),
+        ValueType::Pointer => match pointer_size {
+            4 => int!(u32),
+            _ => int!(u64),
+        },
+        ValueType::CString => text()?.bytes().chain([0]).collect(),
+        ValueType::WString => text()?.encode_utf16().chain([0]).flat_map(u16::to_le_bytes).collect(),
+        ValueType::Bytes => text()?.split_whitespace()
+            .map(|byte| u8::from_str_radix(byte, 16).map_err(|_| MemoryError::InvalidTable))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    // a text or byte freeze longer than the field would overwrite whatever follows it
+    match bytes.len() <= value_size(entry, pointer_size) {
+        true => Ok(bytes),
+        false => Err(MemoryError::InvalidTable),
+    }
+}
+
+/// Parses a json (`format` 0) or toml (`format` 1) table and registers it.
+pub fn load(source: &str, format: u32) -> Result<TableId, MemoryError> {
+    Table::parse(source, format).map(register)
+}
+
+pub fn register(table: Table) -> TableId {
+    let mut registry = TABLES.lock();
+    let id = registry.next_id;
+    registry.next_id += 1;
+    registry.tables.insert(id, table);
+
+    id
+}
+
+pub fn get(id: TableId) -> Option<Table> {
+    TABLES.lock().tables.get(&id).cloned()
+}
+
+pub fn unload(id: TableId) -> bool {
+    TABLES.lock().tables.remove(&id).is_some()
+}
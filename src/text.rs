@@ -0,0 +1,163 @@
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use udbg::memory::MemoryPage;
+
+use crate::{definitions::IMemoryTarget, pattern::find_aligned_occurrences};
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Ascii = 1,
+    Utf8 = 2,
+    Utf16Le = 4,
+}
+
+impl TextEncoding {
+    pub const ALL: [TextEncoding; 3] = [TextEncoding::Ascii, TextEncoding::Utf8, TextEncoding::Utf16Le];
+
+    pub fn natural_alignment(&self) -> usize {
+        match self {
+            TextEncoding::Utf16Le => 2,
+            _ => 1,
+        }
+    }
+
+    fn encode(&self, c: char) -> Option<Vec<u8>> {
+        match self {
+            TextEncoding::Ascii => c.is_ascii().then(|| vec![c as u8]),
+            TextEncoding::Utf8 => Some(c.to_string().into_bytes()),
+            TextEncoding::Utf16Le => Some(c.encode_utf16(&mut [0u16; 2]).iter().flat_map(|unit| unit.to_le_bytes()).collect()),
+        }
+    }
+}
+
+pub struct Needle {
+    /// every requested encoding producing `characters`, e.g. ASCII and UTF-8 of ASCII text
+    pub encodings: Vec<TextEncoding>,
+    /// the accepted byte sequences of every character, all of the same length
+    pub characters: Vec<Vec<Vec<u8>>>,
+    /// the bytes each position accepts in any of the sequences, finds the candidates to check
+    /// against `characters`
+    pub bytes: Vec<Vec<u8>>,
+}
+
+impl Needle {
+    /// Whether `bytes` starts with one of the sequences of every character. Per byte sets alone
+    /// would also take mixes of multibyte characters, e.g. `CE 89` (Ή) for ω / Ω.
+    fn matches(&self, bytes: &[u8]) -> bool {
+        let mut position = 0;
+        self.characters.iter().all(|alternatives| {
+            let len = alternatives[0].len();
+            let matched = bytes.get(position..position + len)
+                .is_some_and(|encoded| alternatives.iter().any(|alternative| alternative == encoded));
+
+            position += len;
+            matched
+        })
+    }
+}
+
+/// Builds one byte pattern per requested encoding (`TextEncoding` flags). Case folding is
+/// simple, per character, and only applies where both cases encode to the same length; the cases
+/// are matched as whole characters.
+/// Encodings yielding the same bytes as an earlier one share its needle, so the bytes are only
+/// searched once but matches are still reported for each of them.
+pub fn build_needles(text: &str, encodings: u32, case_insensitive: bool) -> Vec<Needle> {
+    let mut needles: Vec<Needle> = Vec::new();
+
+    for encoding in TextEncoding::ALL.iter().filter(|encoding| encodings & **encoding as u32 > 0) {
+        let mut characters: Vec<Vec<Vec<u8>>> = Vec::new();
+        let mut bytes: Vec<Vec<u8>> = Vec::new();
+        let mut encodable = true;
+
+        for c in text.chars() {
+            let Some(encoded) = encoding.encode(c) else {
+                encodable = false;
+                break;
+            };
+
+            let mut alternatives = vec![encoded.clone()];
+            if case_insensitive {
+                let folded = [c.to_lowercase().collect::<Vec<_>>(), c.to_uppercase().collect::<Vec<_>>()];
+                alternatives.extend(folded.iter()
+                    .filter(|chars| chars.len() == 1)
+                    .filter_map(|chars| encoding.encode(chars[0]))
+                    .filter(|other| other.len() == encoded.len()));
+            }
+
+            alternatives.sort_unstable();
+            alternatives.dedup();
+
+            for i in 0..encoded.len() {
+                let mut position = alternatives.iter().map(|alternative| alternative[i]).collect::<Vec<_>>();
+                position.sort_unstable();
+                position.dedup();
+                bytes.push(position);
+            }
+
+            characters.push(alternatives);
+        }
+
+        if !encodable || bytes.is_empty() {
+            continue;
+        }
+
+        match needles.iter_mut().find(|needle| needle.characters == characters) {
+            Some(needle) => needle.encodings.push(*encoding),
+            None => needles.push(Needle { encodings: vec![*encoding], characters, bytes }),
+        }
+    }
+
+    needles
+}
+
+/// Searches every needle over the pages, reading each page once. An `alignment` of 0 uses the
+/// natural alignment of each encoding.
+pub fn scan(target: &IMemoryTarget, pages: &[MemoryPage], needles: &[Needle], alignment: usize) -> Vec<(usize, TextEncoding)> {
+    let mut matches: Vec<(usize, TextEncoding)> = pages
+        .par_iter()
+        .flat_map_iter(|page| {
+            let buff = target.read_bytes(page.base, page.size);
+            let mut matches = Vec::new();
+
+            for needle in needles.iter() {
+                // searched once per distinct alignment of the encodings sharing the needle
+                let mut found: Vec<(usize, Vec<usize>)> = Vec::new();
+
+                for encoding in needle.encodings.iter() {
+                    let alignment = match alignment {
+                        0 => encoding.natural_alignment(),
+                        alignment => alignment,
+                    };
+
+                    let index = match found.iter().position(|(searched, _)| *searched == alignment) {
+                        Some(index) => index,
+                        None => {
+                            let candidates = find_aligned_occurrences(&buff, &needle.bytes, page.base, alignment)
+                                .into_iter()
+                                .filter(|addr| needle.matches(&buff[*addr..]))
+                                .collect();
+
+                            found.push((alignment, candidates));
+                            found.len() - 1
+                        }
+                    };
+
+                    matches.extend(found[index].1.iter().map(|addr| (page.base + addr, *encoding)));
+                }
+            }
+
+            matches
+        })
+        .collect();
+
+    matches.sort_by_key(|(address, encoding)| (*address, *encoding as u32));
+    matches
+}
+
+// String match
+// ----------------------------------------------------
+#[repr(C)]
+pub struct CStringMatch {
+    pub address: usize,
+    pub encoding: TextEncoding,
+}
@@ -0,0 +1,54 @@
+// Maintenance tasks, `cargo xtask <task>`
+// ---------------------------------------------------------------
+use std::{path::PathBuf, process::ExitCode};
+
+const HEADER: &str = "include/rsmem.h";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["header"] => header(false),
+        ["header", "--check"] => header(true),
+        _ => {
+            eprintln!("usage: cargo xtask header [--check]");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Regenerates the committed C header, or with `check` fails when it is out of date.
+fn header(check: bool) -> ExitCode {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask lives inside the crate")
+        .to_path_buf();
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("failed to read cbindgen.toml");
+
+    let bindings = match cbindgen::Builder::new().with_crate(&crate_dir).with_config(config).generate() {
+        Ok(bindings) => bindings,
+        Err(err) => {
+            eprintln!("failed to generate {}: {}", HEADER, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let path = crate_dir.join(HEADER);
+    if !check {
+        bindings.write_to_file(&path);
+        return ExitCode::SUCCESS;
+    }
+
+    let mut generated = Vec::new();
+    bindings.write(&mut generated);
+
+    match std::fs::read(&path) {
+        Ok(committed) if committed == generated => ExitCode::SUCCESS,
+        _ => {
+            eprintln!("{} is out of date, run `cargo xtask header`", HEADER);
+            ExitCode::FAILURE
+        }
+    }
+}